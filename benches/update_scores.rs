@@ -0,0 +1,40 @@
+//! Scoring cost as the rounds grow: every pair of players gets compared, so the time grows
+//! quadratically, but the allocations shouldn't (see tests/allocations.rs).
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rust_rps_game::{update_scores, Choice};
+
+fn players(count: usize) -> Vec<(String, Choice)> {
+    (0..count)
+        .map(|i| (format!("player{i}"), Choice::ALL[i % 3].clone()))
+        .collect()
+}
+
+fn bench_update_scores(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_scores");
+    for count in [2, 10, 100, 1000] {
+        let players_details = players(count);
+        let scoreboard = players_details
+            .iter()
+            .map(|(name, _)| (name.clone(), 0))
+            .collect::<HashMap<_, _>>();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &players_details,
+            |b, players_details| {
+                b.iter_batched_ref(
+                    || scoreboard.clone(),
+                    |players_scores| update_scores(black_box(players_details), players_scores),
+                    criterion::BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_update_scores);
+criterion_main!(benches);
@@ -0,0 +1,6038 @@
+//! Rock, Paper, Scissors played with commit-reveal: each player first commits the hash of their
+//! choice & a salt, and only then reveals them, so that no one can change their choice after
+//! seeing the others'.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use blake3::Hash as Blake3Hash;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+// As database for maintaining scores
+pub struct Player {
+    pub name: String,
+    pub score: u32,
+}
+
+/// NOTE: We can also store the game history for each player.
+/// Which option was opted by the player, who was the winner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    pub total_rounds: u32,
+    pub round: Vec<Round>,
+}
+
+impl Game {
+    pub fn from_rounds(total_rounds: u32, rounds: Vec<Round>) -> Self {
+        Self {
+            total_rounds,
+            round: rounds,
+        }
+    }
+
+    pub fn into_rounds(self) -> Vec<Round> {
+        self.round
+    }
+
+    /// Everyone who played a round of the game (or forfeited it), sorted by name.
+    pub fn player_names(&self) -> Vec<&str> {
+        let mut names = self
+            .round
+            .iter()
+            .flat_map(|round| {
+                round
+                    .players
+                    .keys()
+                    .chain(round.forfeits.keys())
+                    .map(String::as_str)
+            })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Record a round played. For long-running sessions, only the most recent
+    /// `max_retained_rounds` are kept in memory: the older ones are first flushed to the JSONL
+    /// `log`, one round per line (see `read_round_log`). Stats needing the full history should
+    /// read it back from the log.
+    pub fn push_round(
+        &mut self,
+        round: Round,
+        max_retained_rounds: Option<NonZeroUsize>,
+        log: &mut impl Write,
+    ) -> std::io::Result<()> {
+        self.round.push(round);
+        let Some(max) = max_retained_rounds else {
+            return Ok(());
+        };
+
+        let evicted = self.round.len().saturating_sub(max.get());
+        for round in &self.round[..evicted] {
+            writeln!(log, "{}", round.to_json())?;
+        }
+        log.flush()?;
+        self.round.drain(..evicted);
+
+        Ok(())
+    }
+
+    /// Sanity check the game, e.g. before saving it or auditing a (hand-edited) game file:
+    /// the round ids are unique & within `1..=total_rounds`, every round has players, and each
+    /// recorded winner (or tie) is indeed the round's outcome as per its choices & forfeits (see
+    /// `Round::rescore`).
+    /// Returns all the inconsistencies found.
+    pub fn validate_consistency(&self) -> Result<(), Vec<GameError>> {
+        let mut errors = vec![];
+        let mut ids = std::collections::HashSet::new();
+        for round in &self.round {
+            if !(1..=self.total_rounds).contains(&round.id) {
+                errors.push(GameError::RoundOutOfRange {
+                    id: round.id,
+                    total_rounds: self.total_rounds,
+                });
+            }
+            if !ids.insert(round.id) {
+                errors.push(GameError::DuplicateRound(round.id));
+            }
+            if round.players.is_empty() && round.forfeits.is_empty() {
+                errors.push(GameError::EmptyRound(round.id));
+            }
+            if round_winner(&round.rescore()) != round.winner {
+                errors.push(GameError::WrongWinner {
+                    id: round.id,
+                    winner: round.winner.clone(),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// What if `player` had played `new_choice` in round `round_id`: the game replayed with that
+/// choice instead (revealed, if they forfeited the round), each round's winner & the scores
+/// worked out again from the choices & forfeits.
+pub fn replay_whatif(
+    game: &Game,
+    round_id: u32,
+    player: &str,
+    new_choice: Choice,
+) -> Result<GameResult, GameError> {
+    let mut game = game.clone();
+    let round = game
+        .round
+        .iter_mut()
+        .find(|round| round.id == round_id)
+        .ok_or(GameError::RoundOutOfRange {
+            id: round_id,
+            total_rounds: game.total_rounds,
+        })?;
+    if !round.players.contains_key(player) && round.forfeits.remove(player).is_none() {
+        return Err(GameError::NotInRound(player.to_string()));
+    }
+    round.players.insert(player.to_string(), new_choice);
+
+    let mut scores = HashMap::new();
+    for round in &mut game.round {
+        let players_scores = round.rescore();
+        round.winner = round_winner(&players_scores);
+        for (name, score) in players_scores {
+            *scores.entry(name).or_insert(0) += score;
+        }
+    }
+
+    Ok(GameResult { game, scores })
+}
+
+/// The two histories recorded the same round differently.
+#[derive(Debug, PartialEq)]
+pub struct MergeConflict {
+    pub id: u32,
+}
+
+impl std::fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "round {} was recorded with different outcomes", self.id)
+    }
+}
+
+impl std::error::Error for MergeConflict {}
+
+/// Reconcile the (partial) histories of a game recorded on two nodes: the union of their rounds
+/// by id, sorted. A round recorded by both must have the same winner & choices.
+pub fn merge_histories(a: &Game, b: &Game) -> Result<Game, MergeConflict> {
+    let mut rounds = a.round.clone();
+    for round in &b.round {
+        match rounds.iter().find(|merged| merged.id == round.id) {
+            Some(merged)
+                if merged.winner != round.winner
+                    || merged.players != round.players
+                    || merged.forfeits != round.forfeits =>
+            {
+                return Err(MergeConflict { id: round.id });
+            }
+            Some(_) => {}
+            None => rounds.push(round.clone()),
+        }
+    }
+    rounds.sort_by_key(|round| round.id);
+
+    Ok(Game::from_rounds(
+        a.total_rounds.max(b.total_rounds),
+        rounds,
+    ))
+}
+
+/// A played game along with its final scores.
+pub struct GameResult {
+    pub game: Game,
+    pub scores: HashMap<String, u32>,
+}
+
+/// Each round of game has these fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Round {
+    pub id: u32,
+    // None if 'Tie'
+    pub winner: Option<String>,
+    pub players: HashMap<String, Choice>,
+    /// The players who forfeited the round, each losing it to every player in `players`:
+    /// `true` if they conceded it (see `RevealState::Forfeit`).
+    pub forfeits: HashMap<String, bool>,
+    /// How long each player took to commit, when measured (see `RoundOptions::commit_times`).
+    pub commit_times: HashMap<String, Duration>,
+    pub timestamp: u32,
+}
+
+impl Round {
+    /// Record a round just played, as scored by `score_round`.
+    pub fn played(
+        id: u32,
+        players_details: &[(String, RevealState)],
+        players_scores: &HashMap<String, u32>,
+    ) -> Self {
+        Self {
+            id,
+            winner: round_winner(players_scores),
+            players: revealed_choices(players_details).into_iter().collect(),
+            forfeits: players_details
+                .iter()
+                .filter_map(|(name, state)| match state {
+                    RevealState::Forfeit { voluntary } => Some((name.clone(), *voluntary)),
+                    _ => None,
+                })
+                .collect(),
+            commit_times: HashMap::new(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs() as u32),
+        }
+    }
+
+    /// The round's players as they ended it: revealed, or forfeited.
+    pub fn players_details(&self) -> Vec<(String, RevealState)> {
+        let revealed = self
+            .players
+            .iter()
+            .map(|(name, choice)| (name.clone(), RevealState::Revealed(choice.clone())));
+        let forfeited = self.forfeits.iter().map(|(name, voluntary)| {
+            (
+                name.clone(),
+                RevealState::Forfeit {
+                    voluntary: *voluntary,
+                },
+            )
+        });
+
+        revealed.chain(forfeited).collect()
+    }
+
+    /// The players' points in the round, worked out again from the recorded choices & forfeits
+    /// (see `score_round`).
+    pub fn rescore(&self) -> HashMap<String, u32> {
+        score_round(&self.players_details()).expect("A recorded round has no pending commit")
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let players = self
+            .players
+            .iter()
+            .map(|(name, choice)| (name.clone(), serde_json::json!(choice.as_str())))
+            .collect::<serde_json::Map<_, _>>();
+        let commit_times_ms = self
+            .commit_times
+            .iter()
+            .map(|(name, time)| (name.clone(), serde_json::json!(time.as_millis() as u64)))
+            .collect::<serde_json::Map<_, _>>();
+
+        serde_json::json!({
+            "id": self.id,
+            "winner": self.winner,
+            "players": players,
+            "forfeits": self.forfeits,
+            "commit_times_ms": commit_times_ms,
+            "timestamp": self.timestamp,
+        })
+    }
+
+    /// Parse a round as written by `to_json`, `None` if it isn't one.
+    pub fn from_json(json: &serde_json::Value) -> Option<Self> {
+        let players = json["players"]
+            .as_object()?
+            .iter()
+            .map(|(name, choice)| Some((name.clone(), choice.as_str()?.parse().ok()?)))
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            id: json["id"].as_u64()?.try_into().ok()?,
+            winner: match &json["winner"] {
+                serde_json::Value::Null => None,
+                winner => Some(winner.as_str()?.to_string()),
+            },
+            players,
+            // logged before forfeits were recorded
+            forfeits: match &json["forfeits"] {
+                serde_json::Value::Null => HashMap::new(),
+                forfeits => forfeits
+                    .as_object()?
+                    .iter()
+                    .map(|(name, voluntary)| Some((name.clone(), voluntary.as_bool()?)))
+                    .collect::<Option<_>>()?,
+            },
+            commit_times: match &json["commit_times_ms"] {
+                serde_json::Value::Null => HashMap::new(),
+                commit_times => commit_times
+                    .as_object()?
+                    .iter()
+                    .map(|(name, ms)| Some((name.clone(), Duration::from_millis(ms.as_u64()?))))
+                    .collect::<Option<_>>()?,
+            },
+            timestamp: json["timestamp"].as_u64()?.try_into().ok()?,
+        })
+    }
+}
+
+/// Read back the rounds flushed to a JSONL log by `Game::push_round`, oldest first.
+pub fn read_round_log(log: impl BufRead) -> std::io::Result<Vec<Round>> {
+    log.lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| {
+            let line = line?;
+            serde_json::from_str(&line)
+                .ok()
+                .and_then(|json| Round::from_json(&json))
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("malformed round in the log: {line}"),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Where the snapshots of a game in progress are saved, so that a crash doesn't lose it all.
+pub trait SnapshotStore {
+    fn save_snapshot(&self, game: &Game) -> std::io::Result<()>;
+}
+
+/// Keeps the latest snapshot of the game in a JSONL file, one round per line: resume the game
+/// from it with `read_round_log`.
+pub struct JsonlSnapshotFile {
+    pub path: PathBuf,
+}
+
+impl SnapshotStore for JsonlSnapshotFile {
+    fn save_snapshot(&self, game: &Game) -> std::io::Result<()> {
+        let mut jsonl = String::new();
+        for round in &game.round {
+            jsonl.push_str(&format!("{}\n", round.to_json()));
+        }
+        // written aside first, so that a crash mid-save leaves the previous snapshot intact
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, jsonl)?;
+        std::fs::rename(tmp, &self.path)
+    }
+}
+
+/// Save a snapshot of the game to the `store` every `every` rounds played, to be called after
+/// each round: a crash then loses at most `every` rounds. Returns whether one got saved.
+pub fn snapshot_if_due(
+    game: &Game,
+    every: NonZeroUsize,
+    store: &dyn SnapshotStore,
+) -> std::io::Result<bool> {
+    let due = !game.round.is_empty() && game.round.len().is_multiple_of(every.get());
+    if due {
+        store.save_snapshot(game)?;
+    }
+
+    Ok(due)
+}
+
+/// How long a match lasts, validated up front so a game can't be configured with zero rounds.
+/// - `BestOf(n)`: play at most `n` rounds, `n` is odd so that the match can't end level.
+/// - `FirstTo(n)`: play until someone reaches `n` points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchLength {
+    BestOf(NonZeroU32),
+    FirstTo(NonZeroU32),
+}
+
+impl MatchLength {
+    pub fn best_of(rounds: u32) -> Result<Self, String> {
+        let rounds = NonZeroU32::new(rounds)
+            .ok_or_else(|| "best-of must be at least 1 round".to_string())?;
+        if rounds.get() % 2 == 0 {
+            return Err(format!(
+                "best-of must be an odd number of rounds so the match can't end level, got {rounds} (try {})",
+                rounds.get() + 1
+            ));
+        }
+
+        Ok(Self::BestOf(rounds))
+    }
+
+    pub fn first_to(score: u32) -> Result<Self, String> {
+        NonZeroU32::new(score)
+            .map(Self::FirstTo)
+            .ok_or_else(|| "first-to must target at least 1 point".to_string())
+    }
+}
+
+/// How the players face each other in a round.
+/// - `EveryOpponent`: everyone plays at once, a point per opponent beaten (see `update_scores`).
+/// - `Knockout`: the players are paired off 1 vs 1 & the loser is out. A tie is replayed, the odd
+///   player out goes through to the next round.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoringRule {
+    EveryOpponent,
+    Knockout,
+}
+
+/// Fewest rounds that could end with a single player strictly ahead of the field, e.g. to
+/// pick a first-to-N default.
+/// With every opponent faced at once, 1 round is enough (one Rock against all Scissors). In a
+/// knockout, at most half of the players are out each round: it takes ⌈log2(num_players)⌉
+/// rounds.
+pub fn min_rounds_for_decisive(num_players: usize, scoring: &ScoringRule) -> u32 {
+    if num_players < 2 {
+        return 0;
+    }
+
+    match scoring {
+        ScoringRule::EveryOpponent => 1,
+        ScoringRule::Knockout => num_players.next_power_of_two().trailing_zeros(),
+    }
+}
+
+/// Who has already clinched the match, if anyone: even if they score nothing in the `remaining`
+/// rounds and every other player wins `win_points` (the most a player can score in a round, one
+/// per opponent) in each of them, no one can catch up with them.
+pub fn rounds_to_clinch(
+    scores: &HashMap<String, u32>,
+    remaining: u32,
+    win_points: u32,
+) -> Option<String> {
+    let (leader, leader_score) = scores.iter().max_by_key(|(_, score)| **score)?;
+    let max_reachable = remaining.saturating_mul(win_points);
+    scores
+        .iter()
+        .filter(|(name, _)| *name != leader)
+        .all(|(_, score)| score.saturating_add(max_reachable) < *leader_score)
+        .then(|| leader.clone())
+}
+
+/// Default cap on the salt size: generous, but a pathological salt can't exhaust the memory.
+pub const DEFAULT_MAX_SALT_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, PartialEq)]
+pub enum GameError {
+    SaltTooLarge {
+        len: usize,
+        max: usize,
+    },
+    IllegalChoice(String),
+    MalformedEntry(String),
+    /// Scoring a round where the player has only committed so far.
+    Unrevealed(String),
+    RoundOutOfRange {
+        id: u32,
+        total_rounds: u32,
+    },
+    DuplicateRound(u32),
+    EmptyRound(u32),
+    /// The recorded winner of the round didn't actually win it.
+    /// `winner` is `None` for a round recorded as a tie.
+    WrongWinner {
+        id: u32,
+        winner: Option<String>,
+    },
+    PlayerCount(PlayerCountError),
+    SchemeMismatch(SchemeMismatch),
+    QrPayload(QrPayloadError),
+    /// No game with this id in the session.
+    UnknownGame(u32),
+    /// A second reveal differing from the player's verified one: the commit binds a single
+    /// choice, so only a broken (or malicious) verifier could have let both through.
+    ChangedReveal {
+        player: String,
+        revealed: Choice,
+        attempted: Choice,
+    },
+    /// Re-committing once reveals started would let the player change their choice after seeing
+    /// the others'.
+    RevealsStarted,
+    NotInRound(String),
+    /// Re-committing for a lost salt, though the player's salt is still there.
+    SaltNotLost(String),
+    /// The scores couldn't be loaded or saved (see `ScoreStore`), e.g. a corrupted file.
+    Store(String),
+}
+
+impl std::fmt::Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::SaltTooLarge { len, max } => {
+                write!(f, "salt is too large: {len} bytes (max {max} bytes)")
+            }
+            GameError::IllegalChoice(choice) => write!(f, "illegal choice: {choice}"),
+            GameError::MalformedEntry(entry) => {
+                write!(f, "malformed entry '{entry}', expected Name=Choice")
+            }
+            GameError::Unrevealed(name) => write!(f, "{name} hasn't revealed their choice yet"),
+            GameError::RoundOutOfRange { id, total_rounds } => {
+                write!(f, "round {id} is out of the game's {total_rounds} rounds")
+            }
+            GameError::DuplicateRound(id) => write!(f, "round {id} is recorded more than once"),
+            GameError::EmptyRound(id) => write!(f, "round {id} has no players"),
+            GameError::WrongWinner {
+                id,
+                winner: Some(winner),
+            } => {
+                write!(
+                    f,
+                    "{winner} is recorded as the winner of round {id} but didn't win it"
+                )
+            }
+            GameError::WrongWinner { id, winner: None } => {
+                write!(f, "round {id} is recorded as a tie but someone won it")
+            }
+            GameError::PlayerCount(err) => write!(f, "{err}"),
+            GameError::SchemeMismatch(err) => write!(f, "{err}"),
+            GameError::QrPayload(err) => write!(f, "{err}"),
+            GameError::UnknownGame(id) => write!(f, "there's no game {id} in the session"),
+            GameError::ChangedReveal {
+                player,
+                revealed,
+                attempted,
+            } => write!(
+                f,
+                "protocol violation: {player} revealed {revealed} but then tried {attempted}"
+            ),
+            GameError::RevealsStarted => {
+                write!(f, "reveals have started, it's too late to re-commit")
+            }
+            GameError::NotInRound(name) => write!(f, "{name} isn't playing this round"),
+            GameError::SaltNotLost(name) => {
+                write!(f, "{name}'s salt isn't lost, their commit can be revealed")
+            }
+            GameError::Store(err) => write!(f, "scores store failure: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::PlayerCount(err) => Some(err),
+            GameError::SchemeMismatch(err) => Some(err),
+            GameError::QrPayload(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for GameError {
+    fn from(err: std::io::Error) -> Self {
+        GameError::Store(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for GameError {
+    fn from(err: serde_json::Error) -> Self {
+        GameError::Store(err.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for GameError {
+    fn from(err: rusqlite::Error) -> Self {
+        GameError::Store(err.to_string())
+    }
+}
+
+impl From<PlayerCountError> for GameError {
+    fn from(err: PlayerCountError) -> Self {
+        GameError::PlayerCount(err)
+    }
+}
+
+impl From<SchemeMismatch> for GameError {
+    fn from(err: SchemeMismatch) -> Self {
+        GameError::SchemeMismatch(err)
+    }
+}
+
+impl From<QrPayloadError> for GameError {
+    fn from(err: QrPayloadError) -> Self {
+        GameError::QrPayload(err)
+    }
+}
+
+/// Runtime version of the guidance on `commit_faster`, for new players.
+pub const SALT_EXPLANATION: &str = "\
+How the commit-reveal works:
+- First, commit to your choice: enter the hash of your choice followed by a salt (a random \
+secret of yours), e.g. blake3(\"Rock\" + salt).
+- Without a salt there would be just 3 possible hashes, so anyone could tell your choice from \
+your commit.
+- Change the salt on every move: a reused salt gives your choice away as soon as you repeat it.
+- Once everyone has committed, reveal your choice & salt: they must match your commit, so no \
+one can change their mind after seeing the others' choices.";
+
+/// Return hash of player's choice & salt.
+/// NOTE: Salt is added to anonymize the choice made by the user, otherwise it
+/// becomes very predictable for just 3 (or limited) choices in this case - Rock, Paper, Scissor.
+/// Suppose, for example if we salt the choice made by Alice, then the hash committed is
+/// unpredictable in terms of guessing the choice made.
+///
+/// Here, the salt is supposed to be changed on every choice made. Otherwise, the choice becomes predictable.
+///
+/// Q. Why not reveal the choice during the commit?
+/// A. This is because in the world of internet in case of online gaming, there is network latency
+/// which is inevitable as participants are most probably from different geographical locations.
+/// So, we want a system that locks the choices made and also is secret enough to not get revealed until asked for.
+///
+/// Q. Why hashing?
+/// A. This is because hashes are irreversible. And in cases of limited choices like here - Rock, Paper, Scissor.
+/// It is recommended to add 'salt' to the choice before committing the hash.
+///
+/// Q. Why is it recommended to change the salt on every move selection?
+/// A. It is done so that the choice made is unpredictable until asked to reveal.
+///
+/// Q. Why Blake3 hash function?
+/// A. It's very fast on modern computers
+///
+/// TODO: Need to check the benchmark with 2 functions
+pub fn commit_faster(choice: &str, salt: &str) -> Blake3Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(choice.as_bytes());
+    hasher.update(salt.as_bytes());
+
+    hasher.finalize()
+}
+
+/// Same as `commit_faster`, but refuses to hash a salt larger than `max_salt_bytes`.
+pub fn commit_checked(
+    choice: &str,
+    salt: &str,
+    max_salt_bytes: usize,
+) -> Result<Blake3Hash, GameError> {
+    if salt.len() > max_salt_bytes {
+        return Err(GameError::SaltTooLarge {
+            len: salt.len(),
+            max: max_salt_bytes,
+        });
+    }
+
+    Ok(commit_faster(choice, salt))
+}
+
+/// Check if the hash of inputs (choice, salt) matches with the commit_hash
+pub fn reveal_faster(commit_hash: Blake3Hash, choice: &str, salt: &str) -> bool {
+    let computed_hash = commit_faster(choice, salt);
+
+    computed_hash.eq(&commit_hash)
+}
+
+/// The choice & salt, padded to a multiple of `block_len` bytes (with a `0x80` byte, then zeros,
+/// so that the padding can't be mistaken for the salt's end): with a `block_len` larger than any
+/// choice & salt, every commit hashes the same number of bytes, whatever the choice.
+pub fn pad_commit_input(choice: &str, salt: &str, block_len: usize) -> Vec<u8> {
+    assert!(block_len > 0, "the block length must be positive");
+
+    let mut input = [choice.as_bytes(), salt.as_bytes(), &[0x80]].concat();
+    input.resize(input.len().div_ceil(block_len) * block_len, 0);
+    input
+}
+
+/// Same as `commit_faster`, but hashing the choice & salt padded to a multiple of `block_len`
+/// (see `pad_commit_input`), so that the commit doesn't leak the length of the choice (e.g.
+/// through timing). Reveal with `reveal_padded`.
+pub fn commit_padded(choice: &str, salt: &str, block_len: usize) -> Blake3Hash {
+    blake3::hash(&pad_commit_input(choice, salt, block_len))
+}
+
+/// Check if the padded hash of inputs (choice, salt) matches with the commit_hash
+pub fn reveal_padded(commit_hash: Blake3Hash, choice: &str, salt: &str, block_len: usize) -> bool {
+    commit_padded(choice, salt, block_len).eq(&commit_hash)
+}
+
+/// How many random salt bits are needed so that brute forcing the salt space takes an
+/// attacker, making `attacker_guesses_per_sec` hash guesses a second, at least `target_seconds`.
+/// The choice isn't counted on for secrecy (with so few of them, it's easily guessed), and the
+/// salt gets `log2(num_choices)` bits of margin on top: so more choices, like a longer target
+/// time, never get a smaller salt.
+pub fn recommended_salt_bits(
+    num_choices: usize,
+    attacker_guesses_per_sec: u64,
+    target_seconds: u64,
+) -> usize {
+    let guesses_needed = attacker_guesses_per_sec as f64 * target_seconds as f64;
+    if guesses_needed < 1.0 {
+        return 0;
+    }
+
+    (guesses_needed * num_choices.max(1) as f64).log2().ceil() as usize
+}
+
+/// Chance that an attacker can deduce a player's choice when the same salt was used for
+/// `reused_salt_count` commits (of uniformly random choices out of `num_choices`).
+/// Under the same salt the same choice gives the same hash, so the commit gives its choice away as
+/// soon as it matches one of the other commits - whose choice gets known once it's revealed.
+/// Hence `1 - (1 - 1/num_choices)^(reused_salt_count - 1)`, 0 if the salt isn't reused.
+pub fn reuse_risk(num_choices: usize, reused_salt_count: usize) -> f64 {
+    if num_choices == 0 {
+        return 0.0;
+    }
+    let others = reused_salt_count.saturating_sub(1) as i32;
+
+    1.0 - (1.0 - 1.0 / num_choices as f64).powi(others)
+}
+
+/// Size of the salts from `generate_salt`: 128 bits, way more than `recommended_salt_bits`.
+pub const SALT_BYTES: usize = 16;
+
+/// A fresh random salt for a commit, hex encoded.
+pub fn generate_salt(rng: &mut impl Rng) -> String {
+    (0..SALT_BYTES)
+        .map(|_| format!("{:02x}", rng.gen::<u8>()))
+        .collect()
+}
+
+/// A random value no single player controls (e.g. to seed who goes first, or the byes): the hash
+/// of all the round's commits, sorted so that it doesn't depend on the order they're listed in.
+/// Only use it once every player committed: the commits are made simultaneously, so no one could
+/// pick theirs to bias it.
+pub fn beacon_from_commits(commits: &[Blake3Hash]) -> [u8; 32] {
+    let mut commits = commits.iter().map(Blake3Hash::as_bytes).collect::<Vec<_>>();
+    commits.sort();
+
+    let mut hasher = blake3::Hasher::new_derive_key("rust-rps-game beacon");
+    for commit in commits {
+        hasher.update(commit);
+    }
+    *hasher.finalize().as_bytes()
+}
+
+/// The salts generated by the tool for its own commits (e.g. in self-play), by player, so that
+/// it can reveal them.
+#[derive(Debug, Default)]
+pub struct SaltStore {
+    pub salts: HashMap<String, String>,
+}
+
+impl SaltStore {
+    /// Commit the player's choice with a fresh salt, kept for their reveal.
+    pub fn commit(&mut self, player_name: &str, choice: &Choice, rng: &mut impl Rng) -> Blake3Hash {
+        let salt = generate_salt(rng);
+        let commit_hash = commit_faster(choice.as_str(), &salt);
+        self.salts.insert(player_name.to_string(), salt);
+
+        commit_hash
+    }
+
+    pub fn salt(&self, player_name: &str) -> Option<&str> {
+        self.salts.get(player_name).map(String::as_str)
+    }
+}
+
+/// Recover from a player's salt missing from the `salts` (their commit can't be revealed): let
+/// them re-commit a fresh choice. To keep the round fair, this is only allowed during the
+/// commit phase, i.e. while no one has revealed (nor forfeited) yet.
+/// Returns the new commit, which replaces the player's one in the round.
+pub fn recommit_lost_salt(
+    player_name: &str,
+    players_details: &mut [(String, RevealState)],
+    salts: &mut SaltStore,
+    choice: &Choice,
+    rng: &mut impl Rng,
+) -> Result<Blake3Hash, GameError> {
+    if players_details
+        .iter()
+        .any(|(_, state)| !matches!(state, RevealState::Committed(_)))
+    {
+        return Err(GameError::RevealsStarted);
+    }
+    let (_, state) = players_details
+        .iter_mut()
+        .find(|(name, _)| name == player_name)
+        .ok_or_else(|| GameError::NotInRound(player_name.to_string()))?;
+    if salts.salt(player_name).is_some() {
+        return Err(GameError::SaltNotLost(player_name.to_string()));
+    }
+
+    let commit_hash = salts.commit(player_name, choice, rng);
+    *state = RevealState::Committed(commit_hash);
+
+    Ok(commit_hash)
+}
+
+/// Estimate the bits of entropy of a salt-generation scheme from a sample of its salts, as the
+/// sum over the positions of the (Shannon) entropy of the byte found at that position in the
+/// samples. A salt shorter than others has an "end" at the positions past its length.
+/// The estimate is capped by the sample size (at most log2(samples.len()) bits per position),
+/// so use a large enough sample.
+pub fn salt_entropy_estimate(samples: &[String]) -> f64 {
+    let longest = samples.iter().map(String::len).max().unwrap_or(0);
+    (0..longest)
+        .map(|position| {
+            let mut counts = HashMap::<Option<u8>, usize>::new();
+            for salt in samples {
+                *counts
+                    .entry(salt.as_bytes().get(position).copied())
+                    .or_insert(0) += 1;
+            }
+
+            counts
+                .values()
+                .map(|&count| {
+                    let p = count as f64 / samples.len() as f64;
+                    -p * p.log2()
+                })
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+/// Rate-limits reveal attempts per player, as defense-in-depth against someone brute forcing
+/// (choice, salt) for a commit made with a weak/short salt.
+/// An attempt within `min_interval` of the same player's previous attempt is rejected.
+pub struct RevealThrottle {
+    min_interval: Duration,
+    last_attempts: HashMap<String, Instant>,
+}
+
+impl RevealThrottle {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_attempts: HashMap::new(),
+        }
+    }
+
+    /// Record the player's attempt at `now`, returns false if it came too fast.
+    pub fn allow(&mut self, player: &str, now: Instant) -> bool {
+        let allowed = self
+            .last_attempts
+            .get(player)
+            .is_none_or(|last| now.duration_since(*last) >= self.min_interval);
+        self.last_attempts.insert(player.to_string(), now);
+
+        allowed
+    }
+}
+
+/// Once the first player reveals, the others have `window` to reveal too: so that no one can
+/// stall the round indefinitely, while everyone gets a fair chance once reveals start.
+pub struct RevealGrace {
+    window: Duration,
+    first_reveal: Option<Instant>,
+}
+
+impl RevealGrace {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            first_reveal: None,
+        }
+    }
+
+    /// Record a (verified) reveal at `now`, returns false if it came past the grace window.
+    pub fn allow(&mut self, now: Instant) -> bool {
+        let first_reveal = *self.first_reveal.get_or_insert(now);
+        now.duration_since(first_reveal) <= self.window
+    }
+
+    /// Whether the window is over at `now`: the players yet to reveal can't anymore.
+    pub fn expired(&self, now: Instant) -> bool {
+        self.first_reveal
+            .is_some_and(|first_reveal| now.duration_since(first_reveal) > self.window)
+    }
+}
+
+// use sha3::{Digest, Sha3_256};
+
+// Q. Why Keccak256 hash function?
+// A. It belongs to SHA3 family which is even stronger than Blake3.
+// fn commit_stronger(choice: &str, salt: &str) -> dyn Digest {
+//     let mut hasher = Sha3_256::new();
+//     hasher.update(choice.as_bytes());
+//     hasher.update(choice.as_bytes());
+//     // read hash digest
+//     let result = hasher.finalize();
+
+//     result
+// }
+
+// fn reveal_stronger(commit_hash: String, inputs: &[String]) -> bool {}
+
+/// The hash function committing the choices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashAlgo {
+    Blake3,
+    Sha3,
+}
+
+impl HashAlgo {
+    const NAMES: [(&'static str, HashAlgo); 2] =
+        [("blake3", HashAlgo::Blake3), ("sha3", HashAlgo::Sha3)];
+
+    /// Commit the choice & salt with this hash, e.g. `commit_faster` for Blake3.
+    pub fn commit(&self, choice: &str, salt: &str) -> [u8; 32] {
+        match self {
+            HashAlgo::Blake3 => *commit_faster(choice, salt).as_bytes(),
+            HashAlgo::Sha3 => {
+                use sha3::{Digest, Sha3_256};
+
+                let mut hasher = Sha3_256::new();
+                hasher.update(choice.as_bytes());
+                hasher.update(salt.as_bytes());
+                hasher.finalize().into()
+            }
+        }
+    }
+
+    /// The hash's own security level against preimage attacks, in bits: Blake3 targets 128
+    /// bits whatever the goal, SHA3-256 gives 256 bits of preimage resistance.
+    pub fn security_bits(&self) -> f64 {
+        match self {
+            HashAlgo::Blake3 => 128.0,
+            HashAlgo::Sha3 => 256.0,
+        }
+    }
+}
+
+/// Result of checking a commit under the selected hash algorithm.
+#[derive(Debug, PartialEq)]
+pub enum CommitCheck {
+    Match,
+    NoMatch,
+    /// It doesn't match, but would under this other algorithm: likely the wrong one got selected
+    /// (the digests are the same length, so only trying tells them apart).
+    OtherAlgo(HashAlgo),
+}
+
+impl std::fmt::Display for CommitCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommitCheck::Match => write!(f, "MATCH"),
+            CommitCheck::NoMatch => write!(f, "NO MATCH"),
+            CommitCheck::OtherAlgo(algo) => {
+                let name = HashAlgo::NAMES
+                    .iter()
+                    .find(|(_, named)| named == algo)
+                    .map_or("", |(name, _)| name);
+                write!(
+                    f,
+                    "NO MATCH: This commit doesn't match the selected algorithm; try --algo {name}"
+                )
+            }
+        }
+    }
+}
+
+/// Check the choice & salt against the commit made with `algo`, hinting at the algorithm it
+/// does match if it's another one.
+pub fn check_commit(algo: HashAlgo, commit: &[u8; 32], choice: &str, salt: &str) -> CommitCheck {
+    if algo.commit(choice, salt) == *commit {
+        return CommitCheck::Match;
+    }
+
+    HashAlgo::NAMES
+        .iter()
+        .map(|&(_, other)| other)
+        .find(|other| *other != algo && other.commit(choice, salt) == *commit)
+        .map_or(CommitCheck::NoMatch, CommitCheck::OtherAlgo)
+}
+
+/// How the players commit: the hash, the salts' entropy & the number of choices to hide.
+pub struct CommitConfig {
+    pub algo: HashAlgo,
+    /// Bits of randomness in the salts, e.g. `8 * SALT_BYTES` for the ones from `generate_salt`.
+    pub salt_bits: usize,
+    pub num_choices: usize,
+}
+
+/// Effective security of the commits in bits, i.e. log2 of the guesses needed to brute force a
+/// commit's choice: every (choice, salt) pair, up to what the hash itself withstands. A tiny
+/// salt with 3 choices gives just a few bits.
+pub fn estimate_security_bits(cfg: &CommitConfig) -> f64 {
+    let brute_force_bits = (cfg.num_choices.max(1) as f64).log2() + cfg.salt_bits as f64;
+
+    brute_force_bits.min(cfg.algo.security_bits())
+}
+
+/// Parse a hash algorithm by its name, in any case (e.g. `blake3`, `SHA3`).
+impl std::str::FromStr for HashAlgo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HashAlgo::NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|&(_, algo)| algo)
+            .ok_or_else(|| {
+                let valid = HashAlgo::NAMES.map(|(name, _)| name).join(", ");
+                format!("unknown hash algorithm '{s}', expected one of: {valid}")
+            })
+    }
+}
+
+/// A commit hash, tagged with the scheme it was made under.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaggedCommit {
+    pub scheme: HashAlgo,
+    pub digest: [u8; 32],
+}
+
+/// A commit made under another scheme than the round's first one.
+#[derive(Debug, PartialEq)]
+pub struct SchemeMismatch {
+    pub expected: HashAlgo,
+    pub found: HashAlgo,
+    /// Position of the offending commit.
+    pub index: usize,
+}
+
+impl std::fmt::Display for SchemeMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "commit #{} uses {:?} but the round uses {:?}",
+            self.index + 1,
+            self.found,
+            self.expected
+        )
+    }
+}
+
+impl std::error::Error for SchemeMismatch {}
+
+/// Check all the commits of a round are made under the same scheme, as a commit made under
+/// another one would just (silently) fail its verification on reveal.
+pub fn assert_uniform_scheme(commits: &[TaggedCommit]) -> Result<(), SchemeMismatch> {
+    let Some(first) = commits.first() else {
+        return Ok(());
+    };
+    match commits
+        .iter()
+        .position(|commit| commit.scheme != first.scheme)
+    {
+        Some(index) => Err(SchemeMismatch {
+            expected: first.scheme,
+            found: commits[index].scheme,
+            index,
+        }),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RevealResult {
+    Verified,
+    /// The reveal is the same move as committed, but spelled differently (e.g. "rock" vs "Rock"),
+    /// and the commit hashes the exact bytes.
+    SpellingMismatch {
+        committed: String,
+    },
+    Mismatch,
+}
+
+impl std::fmt::Display for RevealResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevealResult::Verified => write!(f, "verified"),
+            RevealResult::SpellingMismatch { committed } => write!(
+                f,
+                "case/format mismatch: you committed a different spelling ('{committed}')"
+            ),
+            RevealResult::Mismatch => write!(f, "doesn't match the commit"),
+        }
+    }
+}
+
+/// The choice `entry` spells whatever its case, e.g. "rock" or "ROCK" for Rock.
+fn parse_case_insensitive(entry: &str) -> Option<Choice> {
+    Choice::ALL
+        .into_iter()
+        .find(|choice| choice.as_str().eq_ignore_ascii_case(entry))
+}
+
+/// Like `reveal_faster`, but when the reveal fails tells if the commit was made with another
+/// spelling (case, or the menu number vs the name) of the same choice.
+pub fn reveal_with_normalization_hint(
+    commit_hash: Blake3Hash,
+    choice: &str,
+    salt: &str,
+) -> RevealResult {
+    if reveal_faster(commit_hash, choice, salt) {
+        return RevealResult::Verified;
+    }
+
+    let lowercase = choice.to_lowercase();
+    let capitalized = lowercase
+        .chars()
+        .take(1)
+        .flat_map(char::to_uppercase)
+        .chain(lowercase.chars().skip(1))
+        .collect::<String>();
+    let Ok(normalized) = capitalized.parse::<Choice>() else {
+        return RevealResult::Mismatch;
+    };
+    let number = match normalized {
+        Choice::Rock => "1",
+        Choice::Paper => "2",
+        Choice::Scissors => "3",
+    };
+
+    [
+        lowercase.clone(),
+        choice.to_uppercase(),
+        capitalized,
+        number.to_string(),
+    ]
+    .into_iter()
+    .find(|spelling| spelling != choice && reveal_faster(commit_hash, spelling, salt))
+    .map_or(RevealResult::Mismatch, |committed| {
+        RevealResult::SpellingMismatch { committed }
+    })
+}
+
+/// Record the player's verified reveal. Their choice is final from then on: revealing the same
+/// choice again is a no-op, but a differing one is rejected as a protocol violation.
+/// A forfeit stands.
+pub fn accept_reveal(
+    player_name: &str,
+    state: &mut RevealState,
+    choice: Choice,
+) -> Result<(), GameError> {
+    match state {
+        RevealState::Committed(_) => *state = RevealState::Revealed(choice),
+        RevealState::Revealed(revealed) if *revealed != choice => {
+            return Err(GameError::ChangedReveal {
+                player: player_name.to_string(),
+                revealed: revealed.clone(),
+                attempted: choice,
+            })
+        }
+        RevealState::Revealed(_) | RevealState::Forfeit { .. } => {}
+    }
+
+    Ok(())
+}
+
+/// A portable proof of a reveal, that anyone can check against the commit:
+/// `<commit hash>:<choice>:<salt>`, e.g. `4dfc...:Rock:abhi`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevealProof {
+    pub commit_hash: Blake3Hash,
+    pub choice: Choice,
+    pub salt: String,
+}
+
+impl RevealProof {
+    /// Whether the choice & salt are indeed what got committed.
+    pub fn verify(&self) -> bool {
+        reveal_faster(self.commit_hash, self.choice.as_str(), &self.salt)
+    }
+
+    /// Same as `verify`, for a commit made with `algo`.
+    pub fn check(&self, algo: HashAlgo) -> CommitCheck {
+        check_commit(
+            algo,
+            self.commit_hash.as_bytes(),
+            self.choice.as_str(),
+            &self.salt,
+        )
+    }
+}
+
+impl std::fmt::Display for RevealProof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.commit_hash, self.choice, self.salt)
+    }
+}
+
+/// Parse a proof as printed by its `Display`. The salt comes last, so it may contain `:`.
+impl std::str::FromStr for RevealProof {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, ':');
+        let (Some(commit_hash), Some(choice), Some(salt)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!(
+                "malformed proof '{s}', expected <commit hash>:<choice>:<salt>"
+            ));
+        };
+
+        Ok(Self {
+            commit_hash: commit_hash
+                .parse()
+                .map_err(|_| format!("invalid commit hash: {commit_hash}"))?,
+            choice: choice.parse()?,
+            salt: salt.to_string(),
+        })
+    }
+}
+
+/// Designated-verifier reveal: the player sends the salt to the organizer only, who holds the
+/// commit and publishes just the choice & whether it verified - the salt is never broadcast.
+pub fn designated_reveal(commit_hash: Blake3Hash, choice: Choice, salt: &str) -> (Choice, bool) {
+    let verified = reveal_faster(commit_hash, choice.as_str(), salt);
+
+    (choice, verified)
+}
+
+/// Sign the final game transcript (ed25519) so that any later alteration of it is detectable.
+/// NOTE: ed25519 hashes the message itself (SHA-512) before signing, so the transcript is signed as is.
+pub fn sign_transcript(transcript: &str, key: &SigningKey) -> Signature {
+    key.sign(transcript.as_bytes())
+}
+
+/// Check that the transcript is exactly the one signed by the holder of `key`.
+pub fn verify_transcript_signature(
+    transcript: &str,
+    signature: &Signature,
+    key: &VerifyingKey,
+) -> bool {
+    key.verify(transcript.as_bytes(), signature).is_ok()
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    TimedOut,
+    /// The input got closed (EOF) before a line arrived.
+    Closed,
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::TimedOut => write!(f, "no input in time"),
+            InputError::Closed => write!(f, "input closed"),
+            InputError::Io(err) => write!(f, "failed to read input: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Reads lines on a background thread, so that waiting for one can time out after `timeout`
+/// rather than hang forever. A single thread serves all the reads, however many prompts.
+/// As a `BufRead`, e.g. for `collect_round`, a read timing out fails with `TimedOut`: the commit
+/// & reveal prompts forfeit the player then.
+/// NOTE: A blocking read can't be interrupted, so once the `TimedLines` is dropped its thread
+/// lingers until the reader's next line (or its end): for stdin, until the player hits enter
+/// or closes it.
+pub struct TimedLines {
+    lines: std::sync::mpsc::Receiver<std::io::Result<String>>,
+    timeout: Duration,
+    /// The rest of the line being read through `BufRead`, its line ending included.
+    pending: Vec<u8>,
+    consumed: usize,
+}
+
+impl TimedLines {
+    pub fn new(reader: impl BufRead + Send + 'static, timeout: Duration) -> Self {
+        let (sender, lines) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            lines,
+            timeout,
+            pending: vec![],
+            consumed: 0,
+        }
+    }
+
+    /// The next line (without its line ending), unless none arrives within the timeout.
+    /// A timed out line isn't lost: it's the next one returned.
+    pub fn next_line(&mut self) -> Result<String, InputError> {
+        if self.consumed < self.pending.len() {
+            let rest = String::from_utf8_lossy(&self.pending[self.consumed..]).into_owned();
+            self.consumed = self.pending.len();
+            return Ok(rest.trim_end_matches('\n').to_string());
+        }
+
+        match self.lines.recv_timeout(self.timeout) {
+            Ok(line) => line.map_err(InputError::Io),
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(InputError::TimedOut),
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => Err(InputError::Closed),
+        }
+    }
+}
+
+impl std::io::Read for TimedLines {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+
+        Ok(len)
+    }
+}
+
+impl BufRead for TimedLines {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.consumed == self.pending.len() {
+            self.pending = match self.next_line() {
+                Ok(line) => format!("{line}\n").into_bytes(),
+                Err(InputError::Closed) => vec![],
+                Err(InputError::TimedOut) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        "no input in time",
+                    ))
+                }
+                Err(InputError::Io(err)) => return Err(err),
+            };
+            self.consumed = 0;
+        }
+
+        Ok(&self.pending[self.consumed..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.consumed = (self.consumed + amount).min(self.pending.len());
+    }
+}
+
+/// Whether the prompt gave up waiting for the player (see `TimedLines`).
+fn timed_out(result: &std::io::Result<impl Sized>) -> bool {
+    matches!(result, Err(err) if err.kind() == std::io::ErrorKind::TimedOut)
+}
+
+/// Define a generic function to get user input
+/// Fails with `UnexpectedEof` once the input is closed, rather than prompting forever.
+pub fn collect_input<T: std::str::FromStr>(
+    reader: &mut impl BufRead,
+    prompt: &str,
+) -> std::io::Result<T> {
+    loop {
+        println!("{}", prompt);
+        let mut input = String::new();
+        // don't keep prompting for input that's never going to come
+        if reader.read_line(&mut input)? == 0 {
+            return Err(input_closed());
+        }
+        match input.trim().parse() {
+            Ok(value) => return Ok(value),
+            Err(_) => continue,
+        }
+    }
+}
+
+fn input_closed() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        "Input closed before the game was over",
+    )
+}
+
+/// Like `collect_input` for a salt (or `:forfeit`), but reading no more of the line than
+/// `max_salt_bytes`: a longer salt is `SaltTooLarge` as soon as the cap is passed, the rest of
+/// its line being skipped without ever being buffered.
+pub fn collect_salt(
+    reader: &mut impl BufRead,
+    prompt: &str,
+    max_salt_bytes: usize,
+) -> std::io::Result<Result<Answer<String>, GameError>> {
+    println!("{}", prompt);
+    // room for the salt or `:forfeit`, a line ending (\r\n) & a byte over the cap
+    let limit = max_salt_bytes.max(":forfeit".len()) + 3;
+    let mut line = vec![];
+    if std::io::Read::take(&mut *reader, limit as u64).read_until(b'\n', &mut line)? == 0 {
+        return Err(input_closed());
+    }
+    if line.len() == limit && !line.ends_with(b"\n") {
+        let len = line.len() + skip_line(reader)?;
+        return Ok(Err(GameError::SaltTooLarge {
+            len,
+            max: max_salt_bytes,
+        }));
+    }
+
+    let line = String::from_utf8(line)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+    Ok(match line.trim() {
+        ":forfeit" => Ok(Answer::Forfeit),
+        salt if salt.len() > max_salt_bytes => Err(GameError::SaltTooLarge {
+            len: salt.len(),
+            max: max_salt_bytes,
+        }),
+        salt => Ok(Answer::Value(salt.to_string())),
+    })
+}
+
+/// Skip the rest of the line, chunk by chunk. Returns how many bytes it had (line ending aside).
+fn skip_line(reader: &mut impl BufRead) -> std::io::Result<usize> {
+    let mut skipped = 0;
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(skipped);
+        }
+        match chunk.iter().position(|byte| *byte == b'\n') {
+            Some(end) => {
+                reader.consume(end + 1);
+                return Ok(skipped + end);
+            }
+            None => {
+                let len = chunk.len();
+                reader.consume(len);
+                skipped += len;
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum Choice {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Choice {
+    pub const ALL: [Choice; 3] = [Choice::Rock, Choice::Paper, Choice::Scissors];
+
+    /// Whether this choice beats the other one as per the game rules (see `update_scores`).
+    pub fn beats(&self, other: &Choice) -> bool {
+        matches!(
+            (self, other),
+            (Choice::Rock, Choice::Scissors)
+                | (Choice::Paper, Choice::Rock)
+                | (Choice::Scissors, Choice::Paper)
+        )
+    }
+
+    /// Canonical spelling of the choice, which is what gets committed/revealed
+    /// whichever way the player entered it.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Choice::Rock => "Rock",
+            Choice::Paper => "Paper",
+            Choice::Scissors => "Scissors",
+        }
+    }
+}
+
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where a player is at in the round's commit-reveal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RevealState {
+    Committed(Blake3Hash),
+    Revealed(Choice),
+    /// `voluntary` if the player conceded (`:forfeit`), as opposed to e.g. running out of time.
+    Forfeit {
+        voluntary: bool,
+    },
+}
+
+/// A player's answer to a prompt: either the asked value, or `:forfeit` to concede the round.
+#[derive(Debug, PartialEq)]
+pub enum Answer<T> {
+    Value(T),
+    Forfeit,
+}
+
+impl<T: std::str::FromStr> std::str::FromStr for Answer<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            ":forfeit" => Ok(Answer::Forfeit),
+            _ => s.parse().map(Answer::Value),
+        }
+    }
+}
+
+/// Parse a choice by its name or its number in the menu: 1) Rock 2) Paper 3) Scissors
+impl std::str::FromStr for Choice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Rock" | "1" => Ok(Choice::Rock),
+            "Paper" | "2" => Ok(Choice::Paper),
+            "Scissors" | "3" => Ok(Choice::Scissors),
+            _ => Err(format!("illegal choice: {s}")),
+        }
+    }
+}
+
+/// Every possible round of `players` players: each assignment of a choice to every player (3^n
+/// of them), in a fixed order, e.g. to test the scoring exhaustively.
+pub fn enumerate_rounds(players: usize) -> impl Iterator<Item = Vec<Choice>> {
+    let rounds = 3usize.pow(players as u32);
+    (0..rounds).map(move |mut round| {
+        (0..players)
+            .map(|_| {
+                let choice = Choice::ALL[round % 3].clone();
+                round /= 3;
+                choice
+            })
+            .collect()
+    })
+}
+
+/// Alternative names accepted for the choices, e.g. `Stone` for Rock, as configured by the
+/// organizers in a JSON file: an object of the canonical choice by alias,
+/// `{"Stone": "Rock", "Leaf": "Paper"}`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChoiceAliases {
+    pub aliases: HashMap<String, Choice>,
+}
+
+impl ChoiceAliases {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let aliases = serde_json::from_str::<HashMap<String, String>>(json)
+            .map_err(|err| format!("invalid aliases: {err}"))?
+            .into_iter()
+            .map(|(alias, choice)| Ok((alias, choice.parse()?)))
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { aliases })
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("failed to read the aliases from {}: {err}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    /// Parse a choice as entered: an alias, or else as per `FromStr`.
+    pub fn parse(&self, entry: &str) -> Result<Choice, String> {
+        match self.aliases.get(entry) {
+            Some(choice) => Ok(choice.clone()),
+            None => entry.parse(),
+        }
+    }
+}
+
+/// Declare the winner based on the game logic.
+/// Winner may be 'None' in cases where everyone is killing everyone.
+/// Game rules:
+/// - Rock beats scissors.
+/// - Paper beats rock.
+/// - Scissors beats paper.
+///
+/// In simple line, R < P < S < R
+/// Score rules:
+/// - Tie: when players (all) select same option, score remains unchanged.
+///   Suppose 2 of 3 players select Rock, Rock, Scissor.
+///   So, 1st, 2nd player gain 1 point each & 3rd player 0
+/// - Win/Lose: when either party wins based on game rule.
+pub fn update_scores(
+    players_details: &[(String, Choice)],
+    players_scores: &mut HashMap<String, u32>,
+) {
+    // NOTE: Don't compare the choices by their (derived) order, it isn't cyclic: S > R would give
+    // the win to Scissors against Rock when listed first.
+    // Tally the points by player index first, so that the map is only touched (& a name only
+    // cloned, when new) once per player rather than on every point awarded.
+    let mut points = vec![0u32; players_details.len()];
+    for i in 0..players_details.len() {
+        for j in i + 1..players_details.len() {
+            if players_details[i].1.beats(&players_details[j].1) {
+                points[i] += 1;
+            } else if players_details[j].1.beats(&players_details[i].1) {
+                points[j] += 1;
+            }
+        }
+    }
+
+    for ((name, _), points) in players_details.iter().zip(points) {
+        // players who won nothing aren't added, as before
+        if points == 0 {
+            continue;
+        }
+        match players_scores.get_mut(name) {
+            Some(score) => *score += points,
+            None => {
+                players_scores.insert(name.clone(), points);
+            }
+        }
+    }
+}
+
+/// The player's score, 0 if they haven't scored (or played) at all.
+pub fn score_of(scores: &HashMap<String, u32>, name: &str) -> u32 {
+    scores.get(name).copied().unwrap_or(0)
+}
+
+/// Score a round from scratch: each player's points as per `update_scores`, forfeits included
+/// (see `award_forfeits`).
+/// A player still at their commit can't be scored: it's an error rather than a 0.
+pub fn score_round(
+    players_details: &[(String, RevealState)],
+) -> Result<HashMap<String, u32>, GameError> {
+    if let Some((name, _)) = players_details
+        .iter()
+        .find(|(_, state)| matches!(state, RevealState::Committed(_)))
+    {
+        return Err(GameError::Unrevealed(name.clone()));
+    }
+
+    // maintain a player of HashMap type as no need to sort.
+    let mut players_scores = players_details
+        .iter()
+        .map(|(name, _)| (name.clone(), 0))
+        .collect::<HashMap<String, u32>>();
+
+    // 4. update the scores
+    update_scores(&revealed_choices(players_details), &mut players_scores);
+    award_forfeits(players_details, &mut players_scores);
+
+    Ok(players_scores)
+}
+
+/// Score a round (see `score_round`) among the players who took part, without stalling it on
+/// the absent ones (e.g. in a networked game): a registered player who never committed sits
+/// the round out, not scored, & one who committed but never revealed forfeits it. Each gets a
+/// note written to `log`.
+pub fn resolve_round(
+    registered: &[String],
+    players_details: &[(String, RevealState)],
+    log: &mut impl Write,
+) -> std::io::Result<HashMap<String, u32>> {
+    for name in registered {
+        if !players_details.iter().any(|(player, _)| player == name) {
+            writeln!(log, "{name} didn't commit, they sit this round out")?;
+        }
+    }
+
+    let mut participants = players_details.to_vec();
+    for (name, state) in &mut participants {
+        if let RevealState::Committed(_) = state {
+            writeln!(log, "{name} didn't reveal, they forfeit the round")?;
+            *state = RevealState::Forfeit { voluntary: false };
+        }
+    }
+
+    score_round(&participants)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err.to_string()))
+}
+
+/// A player forfeiting the round loses it to every player who did reveal.
+pub fn award_forfeits(
+    players: &[(String, RevealState)],
+    players_scores: &mut HashMap<String, u32>,
+) {
+    let forfeits = players
+        .iter()
+        .filter(|(_, state)| matches!(state, RevealState::Forfeit { .. }))
+        .count() as u32;
+    for (name, state) in players {
+        if let RevealState::Revealed(_) = state {
+            *players_scores.entry(name.clone()).or_insert(0) += forfeits;
+        }
+    }
+}
+
+/// Longest run of consecutive rounds won by the player, going by the round history.
+/// A tie (no winner) or someone else's win breaks the streak.
+pub fn longest_streak(game: &Game, player: &str) -> u32 {
+    let mut longest = 0;
+    let mut current = 0;
+    for round in &game.round {
+        if round.winner.as_deref() == Some(player) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+
+    longest
+}
+
+/// Upper bound on the players in a game, each pair of them gets compared.
+pub const MAX_PLAYERS: u32 = 64;
+
+#[derive(Debug, PartialEq)]
+pub enum PlayerCountError {
+    NotANumber(String),
+    TooFew(u32),
+    TooMany(String),
+}
+
+impl std::fmt::Display for PlayerCountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayerCountError::NotANumber(input) => {
+                write!(f, "'{input}' isn't a number of players")
+            }
+            PlayerCountError::TooFew(count) => {
+                write!(f, "need at least 2 players, got {count}")
+            }
+            PlayerCountError::TooMany(count) => {
+                write!(f, "at most {MAX_PLAYERS} players can play, got {count}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlayerCountError {}
+
+/// Parse & validate the number of players: 2 up to `MAX_PLAYERS`.
+pub fn parse_player_count(input: &str) -> Result<u32, PlayerCountError> {
+    let input = input.trim();
+    match input.parse::<u32>() {
+        Ok(count) if count < 2 => Err(PlayerCountError::TooFew(count)),
+        Ok(count) if count > MAX_PLAYERS => Err(PlayerCountError::TooMany(input.to_string())),
+        Ok(count) => Ok(count),
+        // too big to even fit
+        Err(_) if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) => {
+            Err(PlayerCountError::TooMany(input.to_string()))
+        }
+        Err(_) => Err(PlayerCountError::NotANumber(input.to_string())),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LobbyError {
+    DuplicateName(String),
+    NotInLobby(String),
+    Full,
+    NotEnoughPlayers(usize),
+}
+
+impl std::fmt::Display for LobbyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LobbyError::DuplicateName(name) => write!(f, "the name {name} is already taken"),
+            LobbyError::NotInLobby(name) => write!(f, "{name} isn't in the lobby"),
+            LobbyError::Full => write!(f, "the lobby is full ({MAX_PLAYERS} players)"),
+            LobbyError::NotEnoughPlayers(count) => {
+                write!(f, "need at least 2 players to start, got {count}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LobbyError {}
+
+/// Players gathering before a match: they join (with a unique name) or leave until the
+/// organizer starts the match.
+#[derive(Debug, Default)]
+pub struct Lobby {
+    players: Vec<String>,
+}
+
+impl Lobby {
+    pub fn join(&mut self, name: &str) -> Result<(), LobbyError> {
+        if self.players.iter().any(|player| player == name) {
+            return Err(LobbyError::DuplicateName(name.to_string()));
+        }
+        if self.players.len() >= MAX_PLAYERS as usize {
+            return Err(LobbyError::Full);
+        }
+        self.players.push(name.to_string());
+
+        Ok(())
+    }
+
+    pub fn leave(&mut self, name: &str) -> Result<(), LobbyError> {
+        let index = self
+            .players
+            .iter()
+            .position(|player| player == name)
+            .ok_or_else(|| LobbyError::NotInLobby(name.to_string()))?;
+        self.players.remove(index);
+
+        Ok(())
+    }
+
+    /// Whether there are enough players to start the match.
+    pub fn ready(&self) -> bool {
+        self.players.len() >= 2
+    }
+
+    /// Close the lobby, returning the match's players in the order they joined.
+    pub fn start(self) -> Result<Vec<String>, LobbyError> {
+        if !self.ready() {
+            return Err(LobbyError::NotEnoughPlayers(self.players.len()));
+        }
+
+        Ok(self.players)
+    }
+}
+
+/// What happens when a reveal doesn't match the player's commit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RevealFailurePolicy {
+    /// Keep asking the player to reveal again.
+    Retry,
+    /// The player forfeits the round.
+    ForfeitOffender,
+    /// For strict tournaments: the whole round is thrown away & replayed with new commits.
+    AbortRound,
+}
+
+/// How a round's commits & reveals get collected.
+pub struct RoundOptions {
+    pub throttle: RevealThrottle,
+    pub max_salt_bytes: usize,
+    pub reveal_failure: RevealFailurePolicy,
+    /// Print each commit as a QR code, for the opponents to scan in in-person games.
+    pub qr: bool,
+    /// A player whose reveal fails to match their commit this many times forfeits the round.
+    /// No limit by default.
+    pub max_reveal_attempts: Option<NonZeroU32>,
+    /// Replay a round where fewer distinct choices got played (up to
+    /// `MAX_LOW_DIVERSITY_REPLAYS` times), e.g. 2 to replay when everyone played the same.
+    pub min_distinct_choices: u32,
+    /// Shuffle the players' reveal order at the start of each round (see `shuffle_players`),
+    /// seeded for a reproducible game. The entry order by default.
+    pub shuffle_players: Option<StdRng>,
+    pub aliases: ChoiceAliases,
+    /// A player revealing later than this after the round's first reveal forfeits (see
+    /// `RevealGrace`). No limit by default.
+    pub reveal_grace: Option<Duration>,
+    /// In self-play, the salts the tool generated for the commits: these reveals only ask for
+    /// the choice, & a player whose salt is missing gets to re-commit before the reveals start
+    /// (see `recommit_lost_salt`).
+    pub salts: Option<SaltStore>,
+    /// Generates the `salts`' fresh salts, for re-commits & replayed rounds. Seeded by the OS
+    /// by default.
+    pub salt_rng: StdRng,
+    /// The time of the commits & reveals, for the throttle, the grace period & the
+    /// `commit_times`. `Instant::now` by default.
+    pub clock: Box<dyn FnMut() -> Instant>,
+    /// Filled by `collect_round`: the time each player took to commit in the round (re-commits
+    /// included), e.g. to record as `Round::commit_times`.
+    pub commit_times: HashMap<String, Duration>,
+}
+
+/// How many times a round is replayed at most for too few distinct choices, so that a group
+/// playing Rock no matter what still gets to finish the game.
+pub const MAX_LOW_DIVERSITY_REPLAYS: u32 = 3;
+
+impl Default for RoundOptions {
+    fn default() -> Self {
+        Self {
+            throttle: RevealThrottle::new(Duration::ZERO),
+            max_salt_bytes: DEFAULT_MAX_SALT_BYTES,
+            reveal_failure: RevealFailurePolicy::Retry,
+            qr: false,
+            max_reveal_attempts: None,
+            min_distinct_choices: 0,
+            shuffle_players: None,
+            aliases: ChoiceAliases::default(),
+            reveal_grace: None,
+            salts: None,
+            salt_rng: StdRng::from_entropy(),
+            clock: Box::new(Instant::now),
+            commit_times: HashMap::new(),
+        }
+    }
+}
+
+/// Shuffle the players, so that no one gets an advantage from their position, e.g. revealing
+/// last. The scoring doesn't depend on the order (see `update_scores`).
+pub fn shuffle_players(players_details: &mut [(String, RevealState)], rng: &mut impl Rng) {
+    players_details.shuffle(rng);
+}
+
+/// Collect the players' commits and then their reveals from `input`, one line per prompt.
+/// A player can type `:forfeit` at any prompt to concede the round.
+pub fn collect_round(
+    input: &mut impl BufRead,
+    options: &mut RoundOptions,
+) -> std::io::Result<Vec<(String, RevealState)>> {
+    // 1. collect players' commit-hash turn-wise
+    let mut players_details = Vec::<(String, RevealState)>::new();
+    options.commit_times.clear();
+
+    loop {
+        // collect players count
+        // loop until player count is valid
+        let players_count = match parse_player_count(&collect_input::<String>(
+            input,
+            "Enter number of players: ",
+        )?) {
+            Ok(players_count) => players_count,
+            Err(err) => {
+                println!("{err}");
+                continue;
+            }
+        };
+
+        // collect players name & commit hashes
+        let mut lobby = Lobby::default();
+        for _ in 0..players_count {
+            let player_name = loop {
+                let player_name = collect_input::<String>(input, "Enter your name: ")?;
+                match lobby.join(&player_name) {
+                    Ok(()) => break player_name,
+                    Err(err) => println!("{err}"),
+                }
+            };
+            let started = (options.clock)();
+            let state = collect_commit(
+                input,
+                "Enter the commit hash of your choice (Rock, Paper, Scissors) with salt (or :forfeit): ",
+            )?;
+            let commit_time = (options.clock)().duration_since(started);
+            options
+                .commit_times
+                .insert(player_name.clone(), commit_time);
+            if options.qr {
+                print_commit_qr(&player_name, &state);
+            }
+            players_details.push((player_name, state));
+        }
+
+        break;
+    }
+
+    // 2. store to DB or the values remain on per session
+
+    // 3. reveal the choices & salt & verify with reveal function
+    let mut low_diversity_replays = 0;
+    loop {
+        if let Some(rng) = &mut options.shuffle_players {
+            shuffle_players(&mut players_details, rng);
+        }
+        println!("commit hashes: {}", debug_commits(&players_details));
+
+        if !collect_reveals(input, &mut std::io::stdout(), &mut players_details, options)? {
+            println!("A reveal didn't match its commit, the round is aborted & replayed.");
+        } else {
+            let distinct_choices = revealed_choices(&players_details)
+                .iter()
+                .map(|(_, choice)| choice.as_str())
+                .collect::<std::collections::HashSet<_>>()
+                .len() as u32;
+            if distinct_choices >= options.min_distinct_choices
+                || low_diversity_replays == MAX_LOW_DIVERSITY_REPLAYS
+            {
+                break;
+            }
+            low_diversity_replays += 1;
+            println!("Only {distinct_choices} distinct choice(s) played, the round is replayed.");
+        }
+
+        for (player_name, state) in players_details.iter_mut() {
+            let started = (options.clock)();
+            *state = match &mut options.salts {
+                // the tool commits for the player, with a new salt
+                Some(salts) => {
+                    let choice = collect_input::<Choice>(
+                        input,
+                        &format!("{player_name}, enter your new choice (1) Rock 2) Paper 3) Scissors): "),
+                    )?;
+                    RevealState::Committed(salts.commit(player_name, &choice, &mut options.salt_rng))
+                }
+                None => collect_commit(
+                    input,
+                    &format!("{player_name}, enter the commit hash of your new choice with a new salt (or :forfeit): "),
+                )?,
+            };
+            let commit_time = (options.clock)().duration_since(started);
+            *options.commit_times.entry(player_name.clone()).or_default() += commit_time;
+            if options.qr {
+                print_commit_qr(player_name, state);
+            }
+        }
+    }
+
+    Ok(players_details)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HashParseError {
+    /// The number of characters entered.
+    WrongLength(usize),
+    InvalidHex(String),
+}
+
+impl std::fmt::Display for HashParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HashParseError::WrongLength(len) => {
+                write!(f, "expected 64 hex chars (32 bytes), got {len}")
+            }
+            HashParseError::InvalidHex(hash) => {
+                write!(f, "expected 64 hex chars (32 bytes), '{hash}' isn't hex")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HashParseError {}
+
+/// Parse a Blake3 hash from its hex, telling what's wrong with it rather than just rejecting it.
+pub fn parse_blake3_hex(s: &str) -> Result<Blake3Hash, HashParseError> {
+    let len = s.chars().count();
+    if len != 64 {
+        return Err(HashParseError::WrongLength(len));
+    }
+
+    Blake3Hash::from_hex(s).map_err(|_| HashParseError::InvalidHex(s.to_string()))
+}
+
+pub fn collect_commit(input: &mut impl BufRead, prompt: &str) -> std::io::Result<RevealState> {
+    loop {
+        let answer = collect_input::<Answer<String>>(input, prompt);
+        if timed_out(&answer) {
+            println!("No commit in time: forfeit");
+            return Ok(RevealState::Forfeit { voluntary: false });
+        }
+        match answer? {
+            Answer::Value(hash) => match parse_blake3_hex(&hash) {
+                Ok(player_commit_hash) => return Ok(RevealState::Committed(player_commit_hash)),
+                Err(err) => println!("{err}"),
+            },
+            Answer::Forfeit => return Ok(RevealState::Forfeit { voluntary: true }),
+        }
+    }
+}
+
+pub const QR_COMMIT_PREFIX: &str = "rps:commit?";
+
+#[derive(Debug, PartialEq)]
+pub enum QrPayloadError {
+    /// Not a commit payload at all, e.g. some other QR code got scanned.
+    UnknownPayload,
+    MissingField(&'static str),
+    InvalidHash(String),
+}
+
+impl std::fmt::Display for QrPayloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QrPayloadError::UnknownPayload => {
+                write!(f, "not a commit QR code, expected {QR_COMMIT_PREFIX}...")
+            }
+            QrPayloadError::MissingField(field) => write!(f, "the QR code has no {field}"),
+            QrPayloadError::InvalidHash(hash) => write!(f, "invalid commit hash: {hash}"),
+        }
+    }
+}
+
+impl std::error::Error for QrPayloadError {}
+
+/// The string to encode in a QR code registering the player along with their commit,
+/// e.g. `rps:commit?player=Alice&hash=4dfc...`. The name's `%`, `&` & `=` are percent-encoded.
+pub fn qr_commit_payload(player_name: &str, commit_hash: Blake3Hash) -> String {
+    let name = player_name
+        .replace('%', "%25")
+        .replace('&', "%26")
+        .replace('=', "%3D");
+    format!("{QR_COMMIT_PREFIX}player={name}&hash={commit_hash}")
+}
+
+/// Terminal QR code of the player's commit payload (see `qr_commit_payload`).
+/// Fails if the payload is too long for a QR code, e.g. for a very long name.
+pub fn commit_qr(
+    player_name: &str,
+    commit_hash: Blake3Hash,
+) -> Result<String, qrcode::types::QrError> {
+    let code = qrcode::QrCode::new(qr_commit_payload(player_name, commit_hash))?;
+    Ok(code
+        .render::<qrcode::render::unicode::Dense1x2>()
+        .quiet_zone(true)
+        .build())
+}
+
+pub fn print_commit_qr(player_name: &str, state: &RevealState) {
+    if let RevealState::Committed(commit_hash) = state {
+        match commit_qr(player_name, *commit_hash) {
+            Ok(qr) => println!("{qr}"),
+            Err(err) => println!("Can't show the commit as a QR code: {err}"),
+        }
+    }
+}
+
+/// Parse a scanned QR payload (see `qr_commit_payload`) into the player's name & commit.
+pub fn parse_qr_commit(payload: &str) -> Result<(String, Blake3Hash), QrPayloadError> {
+    let query = payload
+        .trim()
+        .strip_prefix(QR_COMMIT_PREFIX)
+        .ok_or(QrPayloadError::UnknownPayload)?;
+
+    let (mut name, mut hash) = (None, None);
+    for (key, value) in query.split('&').filter_map(|field| field.split_once('=')) {
+        match key {
+            "player" => name = Some(value),
+            "hash" => hash = Some(value),
+            _ => {}
+        }
+    }
+
+    let name = name
+        .filter(|name| !name.is_empty())
+        .ok_or(QrPayloadError::MissingField("player"))?
+        .replace("%26", "&")
+        .replace("%3D", "=")
+        .replace("%25", "%");
+    let hash = hash.ok_or(QrPayloadError::MissingField("hash"))?;
+    let hash = hash
+        .parse::<Blake3Hash>()
+        .map_err(|_| QrPayloadError::InvalidHash(hash.to_string()))?;
+
+    Ok((name, hash))
+}
+
+/// Let the players whose salt is missing from the tool's `salts` re-commit a fresh choice, as
+/// long as no one revealed yet (see `recommit_lost_salt`).
+fn recover_lost_salts(
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    players_details: &mut [(String, RevealState)],
+    salts: &mut SaltStore,
+    rng: &mut impl Rng,
+) -> std::io::Result<()> {
+    let lost = players_details
+        .iter()
+        .filter(|(name, state)| {
+            matches!(state, RevealState::Committed(_)) && salts.salt(name).is_none()
+        })
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+
+    for player_name in lost {
+        let choice = collect_input::<Choice>(
+            input,
+            &format!(
+                "{player_name}, your salt got lost, please re-commit a choice (1) Rock 2) Paper 3) Scissors): "
+            ),
+        )?;
+        match recommit_lost_salt(&player_name, players_details, salts, &choice, rng) {
+            Ok(_) => writeln!(out, "{player_name} re-committed a new choice."),
+            Err(err) => writeln!(out, "{err}"),
+        }?;
+    }
+
+    Ok(())
+}
+
+/// Ask the players who committed to reveal their choice & salt, and verify it.
+/// Returns false if the round got aborted by a failed reveal (see `RevealFailurePolicy`), an
+/// error if the input closed or the feedback couldn't be written.
+/// The verification's feedback (e.g. "Confirmed: you played Rock") is written to `out`.
+pub fn collect_reveals(
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    players_details: &mut [(String, RevealState)],
+    options: &mut RoundOptions,
+) -> std::io::Result<bool> {
+    if let Some(salts) = &mut options.salts {
+        recover_lost_salts(input, out, players_details, salts, &mut options.salt_rng)?;
+    }
+    let mut grace = options.reveal_grace.map(RevealGrace::new);
+    // run in loop and ask for choice & salt. And then collect it for comparison.
+    for (player_name, state) in players_details.iter_mut() {
+        let RevealState::Committed(player_commit_hash) = *state else {
+            continue;
+        };
+        let mut failed_attempts = 0;
+
+        // Keep asking (looping) the player until the choice & salt doesn't match corresponding to the committed hash.
+        loop {
+            if grace
+                .as_ref()
+                .is_some_and(|grace| grace.expired((options.clock)()))
+            {
+                writeln!(
+                    out,
+                    "The grace period is over, {player_name} forfeits the round."
+                )?;
+                *state = RevealState::Forfeit { voluntary: false };
+                break;
+            }
+            let answer = collect_input::<Answer<String>>(
+                input,
+                &format!(
+                    "{}, please reveal the choice (1) Rock 2) Paper 3) Scissors): ",
+                    player_name
+                ),
+            );
+            if timed_out(&answer) {
+                writeln!(out, "No reveal in time: {player_name} forfeits")?;
+                *state = RevealState::Forfeit { voluntary: false };
+                break;
+            }
+            let choice = match answer? {
+                Answer::Value(choice) => choice,
+                Answer::Forfeit => {
+                    *state = RevealState::Forfeit { voluntary: true };
+                    break;
+                }
+            };
+            // not a reveal attempt yet: the choice can't have been committed at all
+            let entry = choice;
+            let parsed = options
+                .aliases
+                .parse(&entry)
+                .or_else(|err| parse_case_insensitive(&entry).ok_or(err));
+            let choice = match parsed {
+                Ok(choice) => choice,
+                Err(err) => {
+                    writeln!(out, "{err}")?;
+                    continue;
+                }
+            };
+
+            let stored_salt = options
+                .salts
+                .as_ref()
+                .and_then(|salts| salts.salt(player_name));
+            let salt = match stored_salt {
+                Some(salt) => salt.to_string(),
+                None => match collect_salt(
+                    input,
+                    "also please reveal the salt: ",
+                    options.max_salt_bytes,
+                ) {
+                    Err(err) if err.kind() == std::io::ErrorKind::TimedOut => {
+                        writeln!(out, "No reveal in time: {player_name} forfeits")?;
+                        *state = RevealState::Forfeit { voluntary: false };
+                        break;
+                    }
+                    Err(err) => return Err(err),
+                    Ok(Ok(Answer::Value(salt))) => salt,
+                    Ok(Ok(Answer::Forfeit)) => {
+                        *state = RevealState::Forfeit { voluntary: true };
+                        break;
+                    }
+                    Ok(Err(err)) => {
+                        writeln!(out, "{err}")?;
+                        continue;
+                    }
+                },
+            };
+
+            let now = (options.clock)();
+            if !options.throttle.allow(player_name, now) {
+                writeln!(out, "Too many reveal attempts, please slow down.")?;
+                continue;
+            }
+
+            // the canonical choice is what was committed, even if entered by its number. But an
+            // alias is committed as is.
+            let committed_choice = if options.aliases.aliases.contains_key(&entry) {
+                entry.as_str()
+            } else {
+                choice.as_str()
+            };
+            let computed_hash =
+                match commit_checked(committed_choice, &salt, options.max_salt_bytes) {
+                    Ok(computed_hash) => computed_hash,
+                    Err(err) => {
+                        writeln!(out, "{err}")?;
+                        continue;
+                    }
+                };
+            // a choice committed in another spelling (e.g. "rock") is revealed as committed
+            let verified = computed_hash == player_commit_hash
+                || (entry != committed_choice && reveal_faster(player_commit_hash, &entry, &salt));
+            if !verified {
+                let result = reveal_with_normalization_hint(player_commit_hash, &entry, &salt);
+                if let RevealResult::SpellingMismatch { .. } = result {
+                    writeln!(out, "{result}, please reveal it as committed")
+                } else {
+                    writeln!(out, "that choice/salt doesn't match your committed hash")
+                }?;
+
+                failed_attempts += 1;
+                if options
+                    .max_reveal_attempts
+                    .is_some_and(|max| failed_attempts >= max.get())
+                {
+                    writeln!(
+                        out,
+                        "Too many failed reveals, {player_name} forfeits the round."
+                    )?;
+                    *state = RevealState::Forfeit { voluntary: false };
+                    break;
+                }
+
+                match options.reveal_failure {
+                    RevealFailurePolicy::Retry => continue,
+                    RevealFailurePolicy::ForfeitOffender => {
+                        *state = RevealState::Forfeit { voluntary: false };
+                        break;
+                    }
+                    RevealFailurePolicy::AbortRound => return Ok(false),
+                }
+            }
+
+            if grace.as_mut().is_some_and(|grace| !grace.allow(now)) {
+                writeln!(
+                    out,
+                    "Revealed past the grace period, {player_name} forfeits the round."
+                )?;
+                *state = RevealState::Forfeit { voluntary: false };
+                break;
+            }
+
+            // echo the move as classified, e.g. in case "1" wasn't meant as Rock
+            writeln!(out, "Confirmed: you played {choice}")?;
+            // set choice variant to player
+            accept_reveal(player_name, state, choice).expect("A first reveal can't conflict");
+
+            break;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Parse a whole round from a single line like `Alice=R,Bob=S`, for quick scripted rounds in
+/// practice mode (no commit-reveal, the players are trusted).
+/// Each choice is given by its initial, its name or its number in the menu.
+pub fn parse_round_line(line: &str) -> Result<Vec<(String, Choice)>, GameError> {
+    line.split(',')
+        .map(|entry| {
+            let (name, choice) = entry
+                .split_once('=')
+                .map(|(name, choice)| (name.trim(), choice.trim()))
+                .filter(|(name, _)| !name.is_empty())
+                .ok_or_else(|| GameError::MalformedEntry(entry.trim().to_string()))?;
+            let choice = match choice {
+                "R" => Choice::Rock,
+                "P" => Choice::Paper,
+                "S" => Choice::Scissors,
+                _ => choice
+                    .parse()
+                    .map_err(|_| GameError::IllegalChoice(choice.to_string()))?,
+            };
+
+            Ok((name.to_string(), choice))
+        })
+        .collect()
+}
+
+/// Debug rendering of the players' commits, sorted by player name so that it doesn't depend
+/// on the order the commits came in.
+pub fn debug_commits(players_details: &[(String, RevealState)]) -> String {
+    let mut sorted = players_details.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+    format!("{:#?}", sorted)
+}
+
+/// Play one round reading the players' answers from `input`, one line per prompt.
+/// Returns the players' scores.
+pub fn play_round(
+    input: &mut impl BufRead,
+    options: &mut RoundOptions,
+) -> std::io::Result<HashMap<String, u32>> {
+    // every player has revealed or forfeited by the end of `collect_round`
+    Ok(score_round(&collect_round(input, options)?).expect("The round is over"))
+}
+
+/// The choices of the players who did reveal.
+pub fn revealed_choices(players_details: &[(String, RevealState)]) -> Vec<(String, Choice)> {
+    players_details
+        .iter()
+        .filter_map(|(name, state)| match state {
+            RevealState::Revealed(choice) => Some((name.clone(), choice.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// How a round ended, going by the players' scores.
+#[derive(Debug, PartialEq)]
+pub enum RoundOutcome {
+    /// A single player scored the most.
+    Win(String),
+    /// Several players share the top score (sorted by name).
+    Tie(Vec<String>),
+    /// No one scored, e.g. everyone played the same.
+    Stalemate,
+}
+
+pub fn round_outcome(players_scores: &HashMap<String, u32>) -> RoundOutcome {
+    let best = players_scores.values().max().copied().unwrap_or(0);
+    if best == 0 {
+        return RoundOutcome::Stalemate;
+    }
+
+    let mut leaders = players_scores
+        .iter()
+        .filter(|(_, score)| **score == best)
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    if leaders.len() == 1 {
+        RoundOutcome::Win(leaders.remove(0))
+    } else {
+        leaders.sort();
+        RoundOutcome::Tie(leaders)
+    }
+}
+
+/// Whether the round has a single winner, e.g. for a bracket to advance them rather than
+/// replaying the round.
+pub fn is_decisive(outcome: &RoundOutcome) -> bool {
+    matches!(outcome, RoundOutcome::Win(_))
+}
+
+/// The round's winner if a single player scored the most, None if 'Tie'.
+pub fn round_winner(players_scores: &HashMap<String, u32>) -> Option<String> {
+    match round_outcome(players_scores) {
+        RoundOutcome::Win(winner) => Some(winner),
+        _ => None,
+    }
+}
+
+/// How a match ending level on points gets decided.
+/// - `SuddenDeath`: play extra rounds until one wins, so no winner yet.
+/// - `FastestCommits`: the player who took the least time in total to commit over the match.
+/// - `RoundsWon`: the player who won the most rounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchTieBreak {
+    SuddenDeath,
+    FastestCommits,
+    RoundsWon,
+}
+
+/// Break the tie between the players `tied` on points, as per `tie_break`. The commit times
+/// are the ones recorded in the game's rounds (a player without any is the slowest).
+/// None if they're still level, e.g. with the same number of rounds won.
+pub fn break_match_tie(tie_break: MatchTieBreak, tied: &[String], game: &Game) -> Option<String> {
+    fn single_best<K: Ord>(tied: &[String], key: impl Fn(&String) -> K) -> Option<String> {
+        let best = tied.iter().map(&key).min()?;
+        let mut leaders = tied.iter().filter(|name| key(name) == best);
+        match (leaders.next(), leaders.next()) {
+            (Some(leader), None) => Some(leader.clone()),
+            _ => None,
+        }
+    }
+
+    match tie_break {
+        MatchTieBreak::SuddenDeath => None,
+        MatchTieBreak::FastestCommits => single_best(tied, |name| {
+            let mut commit_times = game
+                .round
+                .iter()
+                .filter_map(|round| round.commit_times.get(name))
+                .peekable();
+            match commit_times.peek() {
+                Some(_) => commit_times.sum(),
+                None => Duration::MAX,
+            }
+        }),
+        MatchTieBreak::RoundsWon => single_best(tied, |name| {
+            std::cmp::Reverse(
+                game.round
+                    .iter()
+                    .filter(|round| round.winner.as_ref() == Some(name))
+                    .count(),
+            )
+        }),
+    }
+}
+
+/// Boxed scoreboard for the round: each player's choice & running total, winner marked with `*`.
+pub fn render_frame(round: &Round, scores: &HashMap<String, u32>) -> String {
+    let mut names = round
+        .players
+        .keys()
+        .chain(scores.keys())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+
+    let name_width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+    let mut lines = vec![format!("Round {}", round.id)];
+    for name in names {
+        let marker = if round.winner.as_ref() == Some(name) {
+            '*'
+        } else {
+            ' '
+        };
+        let choice = round.players.get(name).map_or("-", Choice::as_str);
+        let score = scores.get(name).copied().unwrap_or(0);
+        lines.push(format!(
+            "{marker} {name:<name_width$}  {choice:<8}  {score:>3}"
+        ));
+    }
+
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let border = format!("+{}+", "-".repeat(width + 2));
+    let mut frame = vec![border.clone()];
+    for (i, line) in lines.iter().enumerate() {
+        frame.push(format!("| {line:<width$} |"));
+        if i == 0 {
+            frame.push(border.clone());
+        }
+    }
+    frame.push(border);
+
+    frame.join("\n")
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Self-contained HTML match summary: leaderboard, a table row per round & a histogram of the
+/// choices played (inline SVG bars).
+pub fn render_html(result: &GameResult) -> String {
+    let mut leaderboard = result.scores.iter().collect::<Vec<_>>();
+    leaderboard.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Rock, Paper, Scissors</title></head>\n<body>\n",
+    );
+    match round_winner(&result.scores) {
+        Some(winner) => html += &format!("<h1>{} wins!</h1>\n", escape_html(&winner)),
+        None => html += "<h1>It's a tie!</h1>\n",
+    }
+
+    html += "<h2>Leaderboard</h2>\n<ol>\n";
+    for (name, score) in leaderboard {
+        html += &format!("<li>{}: {score}</li>\n", escape_html(name));
+    }
+    html += "</ol>\n";
+
+    html += "<h2>Rounds</h2>\n<table>\n<tr><th>Round</th><th>Choices</th><th>Winner</th></tr>\n";
+    for round in &result.game.round {
+        let mut players = round.players.iter().collect::<Vec<_>>();
+        players.sort_by(|a, b| a.0.cmp(b.0));
+        let choices = players
+            .iter()
+            .map(|(name, choice)| format!("{}: {}", escape_html(name), choice.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let winner = round
+            .winner
+            .as_deref()
+            .map_or("Tie".to_string(), escape_html);
+        html += &format!(
+            "<tr><td>{}</td><td>{choices}</td><td>{winner}</td></tr>\n",
+            round.id
+        );
+    }
+    html += "</table>\n";
+
+    let histogram = [Choice::Rock, Choice::Paper, Choice::Scissors].map(|choice| {
+        let played = result
+            .game
+            .round
+            .iter()
+            .flat_map(|round| round.players.values())
+            .filter(|played| **played == choice)
+            .count();
+        (choice, played)
+    });
+    let most_played = histogram
+        .iter()
+        .map(|(_, played)| *played)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    html += "<h2>Choices</h2>\n<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"320\" height=\"90\">\n";
+    for (i, (choice, played)) in histogram.iter().enumerate() {
+        let y = i * 30;
+        html += &format!(
+            "<text x=\"0\" y=\"{}\">{}</text><rect x=\"80\" y=\"{y}\" width=\"{}\" height=\"20\" fill=\"steelblue\"/><text x=\"290\" y=\"{}\">{played}</text>\n",
+            y + 15,
+            choice.as_str(),
+            played * 200 / most_played,
+            y + 15,
+        );
+    }
+    html += "</svg>\n</body>\n</html>\n";
+
+    html
+}
+
+/// The scores in the shape a leaderboard service (HTTP JSON API) expects:
+/// `{"game_id", "timestamp", "scores": [{"player", "score", "rank"}]}`, best first.
+/// Players level on score share the rank, the next one skipping as many (1, 2, 2, 4).
+pub fn to_scoreboard_payload(scores: &HashMap<String, u32>, game_id: &str) -> serde_json::Value {
+    let mut sorted = scores.iter().collect::<Vec<_>>();
+    sorted.sort_by(|(name_a, a), (name_b, b)| b.cmp(a).then(name_a.cmp(name_b)));
+
+    let entries = sorted
+        .iter()
+        .map(|(name, score)| {
+            let rank = 1 + sorted.iter().filter(|(_, other)| other > score).count();
+            serde_json::json!({ "player": name, "score": score, "rank": rank })
+        })
+        .collect::<Vec<_>>();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    serde_json::json!({ "game_id": game_id, "timestamp": timestamp, "scores": entries })
+}
+
+/// Result card to share on social media: the winner & the final scores, sized as a share image.
+pub fn result_card_svg(game: &Game, scores: &HashMap<String, u32>) -> String {
+    let mut leaderboard = scores.iter().collect::<Vec<_>>();
+    leaderboard.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    let headline = match round_winner(scores) {
+        Some(winner) => format!("{} wins!", escape_html(&winner)),
+        None => "It's a tie!".to_string(),
+    };
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1200\" height=\"630\" viewBox=\"0 0 1200 630\">\n\
+         <rect width=\"1200\" height=\"630\" fill=\"#1e1e2e\"/>\n\
+         <text x=\"600\" y=\"120\" font-size=\"48\" fill=\"#cdd6f4\" text-anchor=\"middle\">Rock, Paper, Scissors - {} rounds</text>\n\
+         <text x=\"600\" y=\"230\" font-size=\"80\" fill=\"#f9e2af\" text-anchor=\"middle\">{headline}</text>\n",
+        game.round.len()
+    );
+    for (i, (name, score)) in leaderboard.iter().take(5).enumerate() {
+        svg += &format!(
+            "<text x=\"600\" y=\"{}\" font-size=\"40\" fill=\"#cdd6f4\" text-anchor=\"middle\">{}: {score}</text>\n",
+            330 + i * 60,
+            escape_html(name)
+        );
+    }
+    svg += "</svg>\n";
+
+    svg
+}
+
+/// Paces the narrations (see `dramatic_reveal` & `replay_game`).
+pub trait Clock {
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// The real clock: a pause blocks the thread.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn sleep(&mut self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// No pauses, e.g. to narrate all at once.
+impl Clock for () {
+    fn sleep(&mut self, _duration: Duration) {}
+}
+
+/// For streamed games: reveal the choices one player at a time, pausing `delay` (on the `clock`)
+/// before each of them and before announcing the round's winner.
+/// Every player must have revealed or forfeited (see `score_round`).
+pub fn dramatic_reveal(
+    players_details: &[(String, RevealState)],
+    delay: Duration,
+    clock: &mut impl Clock,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    let players_scores = score_round(players_details)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err.to_string()))?;
+    for (name, state) in players_details {
+        clock.sleep(delay);
+        match state {
+            RevealState::Revealed(choice) => writeln!(out, "{name} played {}!", choice.as_str())?,
+            _ => writeln!(out, "{name} forfeited!")?,
+        }
+    }
+
+    clock.sleep(delay);
+    match round_winner(&players_scores) {
+        Some(winner) => writeln!(out, "{winner} wins the round!"),
+        None => writeln!(out, "No winner this round."),
+    }
+}
+
+/// Pause between the rounds of a replay at normal (1x) speed.
+pub const REPLAY_ROUND_DELAY: Duration = Duration::from_secs(2);
+
+/// For live re-broadcasts: narrate a saved game round by round, pausing (on the `clock`)
+/// between the rounds for `REPLAY_ROUND_DELAY` scaled by `speed`, e.g. 2.0 to replay twice as
+/// fast. The players of a round are narrated in name order.
+pub fn replay_game(
+    game: &Game,
+    speed: f64,
+    clock: &mut impl Clock,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    let invalid_speed = || {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("the replay speed must be positive, got {speed}"),
+        )
+    };
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err(invalid_speed());
+    }
+    // e.g. a speed so slow that the pause overflows a `Duration`
+    let delay = Duration::try_from_secs_f64(REPLAY_ROUND_DELAY.as_secs_f64() / speed)
+        .map_err(|_| invalid_speed())?;
+
+    for (i, round) in game.round.iter().enumerate() {
+        if i > 0 {
+            clock.sleep(delay);
+        }
+        let mut players = round.players.iter().collect::<Vec<_>>();
+        players.sort_by_key(|(name, _)| *name);
+        let moves = players
+            .iter()
+            .map(|(name, choice)| format!("{name} played {choice}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match &round.winner {
+            Some(winner) => writeln!(out, "Round {}: {moves}. {winner} wins!", round.id)?,
+            None => writeln!(out, "Round {}: {moves}. No winner.", round.id)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// A player's move in a round played headless: their commit, then their reveal of it.
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub round: u32,
+    pub player: String,
+    pub commit_hash: Blake3Hash,
+    pub choice: Choice,
+    pub salt: String,
+}
+
+/// A reveal which didn't match the player's commit: they forfeited the round.
+#[derive(Debug, PartialEq)]
+pub struct VerificationFailure {
+    pub round: u32,
+    pub player: String,
+}
+
+/// The outcome of `run_headless`.
+pub struct HeadlessResult {
+    pub result: GameResult,
+    pub failures: Vec<VerificationFailure>,
+}
+
+/// Play a whole game of `total_rounds` without any IO, e.g. for embedding it: all the moves (one
+/// per player per round) are given upfront, and each round goes through the commit-reveal &
+/// gets scored. A player whose reveal doesn't match their commit forfeits the round.
+/// Rounds without any move aren't played.
+pub fn run_headless(total_rounds: u32, moves: Vec<Move>) -> HeadlessResult {
+    let mut result = GameResult {
+        game: Game::from_rounds(total_rounds, vec![]),
+        scores: HashMap::new(),
+    };
+    let mut failures = vec![];
+
+    for id in 1..=total_rounds {
+        // 1. everyone commits
+        let round_moves = moves.iter().filter(|m| m.round == id).collect::<Vec<_>>();
+        if round_moves.is_empty() {
+            continue;
+        }
+        let mut players_details = round_moves
+            .iter()
+            .map(|m| (m.player.clone(), RevealState::Committed(m.commit_hash)))
+            .collect::<Vec<_>>();
+
+        // 2. then reveals
+        for (m, (_, state)) in round_moves.iter().zip(players_details.iter_mut()) {
+            if reveal_faster(m.commit_hash, m.choice.as_str(), &m.salt) {
+                *state = RevealState::Revealed(m.choice.clone());
+            } else {
+                *state = RevealState::Forfeit { voluntary: false };
+                failures.push(VerificationFailure {
+                    round: id,
+                    player: m.player.clone(),
+                });
+            }
+        }
+
+        // 3. & the round is scored
+        let players_scores = score_round(&players_details).expect("Every player revealed");
+        result
+            .game
+            .round
+            .push(Round::played(id, &players_details, &players_scores));
+        for (name, score) in players_scores {
+            *result.scores.entry(name).or_insert(0) += score;
+        }
+    }
+
+    HeadlessResult { result, failures }
+}
+
+/// Season leaderboard (wins per player) shared between the games running at the same time.
+pub type Standings = Arc<Mutex<HashMap<String, u32>>>;
+
+/// Record a win in the shared standings, safe to call from several game threads.
+pub fn record_result(store: &Standings, winner: &str) {
+    let mut standings = store.lock().expect("Standings lock poisoned");
+    *standings.entry(winner.to_string()).or_insert(0) += 1;
+}
+
+/// Several independent games (e.g. 1v1s) run by an organizer at the same time.
+#[derive(Default)]
+pub struct Session {
+    pub games: HashMap<u32, GameResult>,
+}
+
+impl Session {
+    pub fn add_game(&mut self, id: u32, total_rounds: u32) {
+        self.games.insert(
+            id,
+            GameResult {
+                game: Game::from_rounds(total_rounds, vec![]),
+                scores: HashMap::new(),
+            },
+        );
+    }
+
+    /// Score a round played in the game `id` and add it to that game only.
+    pub fn record_round(
+        &mut self,
+        id: u32,
+        players_details: &[(String, RevealState)],
+    ) -> Result<(), GameError> {
+        let result = self.games.get_mut(&id).ok_or(GameError::UnknownGame(id))?;
+        let players_scores = score_round(players_details)?;
+        let round_id = result.game.round.len() as u32 + 1;
+        result
+            .game
+            .round
+            .push(Round::played(round_id, players_details, &players_scores));
+        for (name, score) in players_scores {
+            *result.scores.entry(name).or_insert(0) += score;
+        }
+
+        Ok(())
+    }
+
+    /// The players' scores over all of the session's games.
+    pub fn combined_standings(&self) -> HashMap<String, u32> {
+        let mut standings = HashMap::new();
+        for result in self.games.values() {
+            for (name, score) in &result.scores {
+                *standings.entry(name.clone()).or_insert(0) += score;
+            }
+        }
+
+        standings
+    }
+}
+
+/// Where the season's scores persist between games, e.g. a JSON file (`JsonFileStore`).
+pub trait ScoreStore {
+    /// The scores saved so far, none at first.
+    fn load(&self) -> Result<HashMap<String, u32>, GameError>;
+    fn save(&self, scores: &HashMap<String, u32>) -> Result<(), GameError>;
+}
+
+/// Keeps the scores in a JSON file, as an object of the players' scores by name.
+pub struct JsonFileStore {
+    pub path: PathBuf,
+}
+
+impl ScoreStore for JsonFileStore {
+    fn load(&self) -> Result<HashMap<String, u32>, GameError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            // no game saved yet
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&self, scores: &HashMap<String, u32>) -> Result<(), GameError> {
+        let json = serde_json::to_string_pretty(scores)?;
+        std::fs::write(&self.path, json)?;
+
+        Ok(())
+    }
+}
+
+/// Keeps the scores, along with the history of the rounds played, in a SQLite database so that
+/// they can be queried across many games.
+pub struct SqliteStore {
+    conn: rusqlite::Connection,
+}
+
+impl SqliteStore {
+    pub fn open(path: &std::path::Path) -> rusqlite::Result<Self> {
+        Self::init(rusqlite::Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(rusqlite::Connection::open_in_memory()?)
+    }
+
+    pub fn init(conn: rusqlite::Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS scores (
+                player TEXT PRIMARY KEY,
+                score INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS rounds (
+                id INTEGER PRIMARY KEY,
+                round_id INTEGER NOT NULL,
+                winner TEXT,
+                timestamp INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS choices (
+                round INTEGER NOT NULL REFERENCES rounds (id),
+                player TEXT NOT NULL,
+                choice TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS forfeits (
+                round INTEGER NOT NULL REFERENCES rounds (id),
+                player TEXT NOT NULL,
+                voluntary INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS commit_times (
+                round INTEGER NOT NULL REFERENCES rounds (id),
+                player TEXT NOT NULL,
+                millis INTEGER NOT NULL
+            );",
+        )?;
+
+        Ok(Self { conn })
+    }
+
+    /// Add the round to the history.
+    pub fn save_round(&self, round: &Round) -> rusqlite::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO rounds (round_id, winner, timestamp) VALUES (?1, ?2, ?3)",
+            rusqlite::params![round.id, round.winner, round.timestamp],
+        )?;
+        let row = tx.last_insert_rowid();
+        for (name, choice) in &round.players {
+            tx.execute(
+                "INSERT INTO choices (round, player, choice) VALUES (?1, ?2, ?3)",
+                rusqlite::params![row, name, choice.as_str()],
+            )?;
+        }
+        for (name, voluntary) in &round.forfeits {
+            tx.execute(
+                "INSERT INTO forfeits (round, player, voluntary) VALUES (?1, ?2, ?3)",
+                rusqlite::params![row, name, voluntary],
+            )?;
+        }
+        for (name, time) in &round.commit_times {
+            tx.execute(
+                "INSERT INTO commit_times (round, player, millis) VALUES (?1, ?2, ?3)",
+                rusqlite::params![row, name, time.as_millis() as i64],
+            )?;
+        }
+
+        tx.commit()
+    }
+
+    /// The history of the rounds played, oldest first.
+    pub fn rounds(&self) -> rusqlite::Result<Vec<Round>> {
+        let mut rounds = self
+            .conn
+            .prepare("SELECT id, round_id, winner, timestamp FROM rounds ORDER BY id")?
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    Round {
+                        id: row.get(1)?,
+                        winner: row.get(2)?,
+                        players: HashMap::new(),
+                        forfeits: HashMap::new(),
+                        commit_times: HashMap::new(),
+                        timestamp: row.get(3)?,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut choices = self
+            .conn
+            .prepare("SELECT player, choice FROM choices WHERE round = ?1")?;
+        let mut forfeits = self
+            .conn
+            .prepare("SELECT player, voluntary FROM forfeits WHERE round = ?1")?;
+        let mut commit_times = self
+            .conn
+            .prepare("SELECT player, millis FROM commit_times WHERE round = ?1")?;
+        for (row, round) in &mut rounds {
+            for choice in choices.query_map([*row], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })? {
+                let (name, choice) = choice?;
+                let choice = choice.parse().map_err(|_| {
+                    rusqlite::Error::InvalidColumnType(
+                        1,
+                        "choice".into(),
+                        rusqlite::types::Type::Text,
+                    )
+                })?;
+                round.players.insert(name, choice);
+            }
+            for forfeit in forfeits.query_map([*row], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+            })? {
+                let (name, voluntary) = forfeit?;
+                round.forfeits.insert(name, voluntary);
+            }
+            for commit_time in commit_times.query_map([*row], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })? {
+                let (name, millis) = commit_time?;
+                round
+                    .commit_times
+                    .insert(name, Duration::from_millis(millis as u64));
+            }
+        }
+
+        Ok(rounds.into_iter().map(|(_, round)| round).collect())
+    }
+}
+
+impl ScoreStore for SqliteStore {
+    fn load(&self) -> Result<HashMap<String, u32>, GameError> {
+        let scores = self
+            .conn
+            .prepare("SELECT player, score FROM scores")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(scores)
+    }
+
+    fn save(&self, scores: &HashMap<String, u32>) -> Result<(), GameError> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (name, score) in scores {
+            tx.execute(
+                "INSERT INTO scores (player, score) VALUES (?1, ?2)
+                ON CONFLICT (player) DO UPDATE SET score = excluded.score",
+                rusqlite::params![name, score],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+}
+
+/// Add the round's scores to the ones in the store, returning the new totals.
+pub fn record_scores(
+    store: &dyn ScoreStore,
+    players_scores: &HashMap<String, u32>,
+) -> Result<HashMap<String, u32>, GameError> {
+    let mut totals = store.load()?;
+    for (name, score) in players_scores {
+        *totals.entry(name.clone()).or_insert(0) += score;
+    }
+    store.save(&totals)?;
+
+    Ok(totals)
+}
+
+/// Cheap tripwire for scoring bugs: with pairwise scoring a player wins at most a point
+/// against each opponent in a round, plus a point per opponent forfeiting it, so no one can have
+/// more than that over the rounds they revealed in.
+pub fn scores_plausible(game: &Game, scores: &HashMap<String, u32>) -> bool {
+    scores.iter().all(|(name, score)| {
+        let max_score = game
+            .round
+            .iter()
+            .filter(|round| round.players.contains_key(name))
+            .map(|round| (round.players.len() - 1 + round.forfeits.len()) as u32)
+            .sum::<u32>();
+        *score <= max_score
+    })
+}
+
+/// Each player's running score after each round (forfeits included), for charting the
+/// progression. Every player gets a value per round, carrying their score over the rounds they didn't play.
+pub fn score_timeline(game: &Game) -> HashMap<String, Vec<u32>> {
+    let mut timeline = HashMap::<String, Vec<u32>>::new();
+    for round in &game.round {
+        for name in round.players.keys().chain(round.forfeits.keys()) {
+            timeline.entry(name.clone()).or_default();
+        }
+    }
+
+    let mut totals = HashMap::<String, u32>::new();
+    for round in &game.round {
+        for (name, score) in round.rescore() {
+            *totals.entry(name).or_insert(0) += score;
+        }
+        for (name, scores) in timeline.iter_mut() {
+            scores.push(totals.get(name).copied().unwrap_or(0));
+        }
+    }
+
+    timeline
+}
+
+/// Every round in which both players played, in order.
+pub fn rounds_with<'a>(game: &'a Game, a: &str, b: &str) -> Vec<&'a Round> {
+    game.round
+        .iter()
+        .filter(|round| round.players.contains_key(a) && round.players.contains_key(b))
+        .collect()
+}
+
+/// Quick-play matchmaking: pair the two longest-waiting players of the queue (oldest first),
+/// a single leftover keeps waiting.
+pub fn matchmake(queue: &mut Vec<String>) -> Option<(String, String)> {
+    if queue.len() < 2 {
+        return None;
+    }
+    let mut pair = queue.drain(..2);
+
+    Some((pair.next()?, pair.next()?))
+}
+
+/// How exploitable a player's moves are by naive opponents, from 0 to 1: the share of their
+/// moves (after the first) that the best of a few simple predictors would have guessed right.
+/// The predictors: they repeat their last move, they play their most frequent move so far
+/// (ties going to the latest one), or they play the counter to their own last move as if it won
+/// (the moves alone don't tell the wins), e.g. Paper after Rock. Random play scores about 1/3,
+/// always the same move 1.
+pub fn predictability(moves: &[Choice]) -> f64 {
+    if moves.len() < 2 {
+        return 0.0;
+    }
+
+    let (mut repeats, mut frequent, mut counters) = (0, 0, 0);
+    let mut counts = HashMap::new();
+    for (i, window) in moves.windows(2).enumerate() {
+        let (last, next) = (&window[0], &window[1]);
+        *counts.entry(last).or_insert(0) += 1;
+
+        if next == last {
+            repeats += 1;
+        }
+        if next.beats(last) {
+            counters += 1;
+        }
+        // the last of the maximums, i.e. the latest played
+        let most_frequent = moves[..=i]
+            .iter()
+            .max_by_key(|choice| counts[choice])
+            .expect("At least a move played");
+        if next == most_frequent {
+            frequent += 1;
+        }
+    }
+
+    repeats.max(frequent).max(counters) as f64 / (moves.len() - 1) as f64
+}
+
+/// Rating of a player new to `update_elo`.
+pub const ELO_INITIAL_RATING: f64 = 1500.0;
+/// How much a single game can move the ratings in `update_elo`.
+pub const ELO_K_FACTOR: f64 = 32.0;
+
+/// Update the (Elo) ratings of the two players after `winner` beat `loser`, with the standard
+/// `ELO_K_FACTOR`. See `update_elo_with` for a tie or another K-factor.
+pub fn update_elo(ratings: &mut HashMap<String, f64>, winner: &str, loser: &str) {
+    update_elo_with(ratings, winner, loser, 1.0, ELO_K_FACTOR);
+}
+
+/// Standard Elo update of the ratings of players `a` & `b` (`ELO_INITIAL_RATING` if unrated)
+/// after a game where `a` scored `score` (1 for a win, 0.5 for a tie, 0 for a loss): each moves
+/// by `k` times the gap between their actual & expected score,
+/// `1 / (1 + 10^((rating_b - rating_a) / 400))` for `a`.
+pub fn update_elo_with(ratings: &mut HashMap<String, f64>, a: &str, b: &str, score: f64, k: f64) {
+    let rating_a = ratings.get(a).copied().unwrap_or(ELO_INITIAL_RATING);
+    let rating_b = ratings.get(b).copied().unwrap_or(ELO_INITIAL_RATING);
+    let expected_a = 1.0 / (1.0 + 10f64.powf((rating_b - rating_a) / 400.0));
+    let delta = k * (score - expected_a);
+
+    ratings.insert(a.to_string(), rating_a + delta);
+    ratings.insert(b.to_string(), rating_b - delta);
+}
+
+/// How often each choice won when it was played over the game: the rounds won by players who
+/// played it, out of the times it got played (by any player). Choices never played are left out.
+pub fn choice_win_rates(game: &Game) -> HashMap<Choice, f64> {
+    let mut record = HashMap::<Choice, (u32, u32)>::new();
+    for round in &game.round {
+        for (name, choice) in &round.players {
+            let (wins, played) = record.entry(choice.clone()).or_insert((0, 0));
+            *played += 1;
+            if round.winner.as_ref() == Some(name) {
+                *wins += 1;
+            }
+        }
+    }
+
+    record
+        .into_iter()
+        .map(|(choice, (wins, played))| (choice, wins as f64 / played as f64))
+        .collect()
+}
+
+/// Head-to-head record of `a` against `b` as (wins, losses, ties) of `a`,
+/// comparing their choices in every round both of them played.
+pub fn head_to_head(game: &Game, a: &str, b: &str) -> (u32, u32, u32) {
+    let mut record = (0, 0, 0);
+    for round in rounds_with(game, a, b) {
+        let (choice_a, choice_b) = (&round.players[a], &round.players[b]);
+        if choice_a.beats(choice_b) {
+            record.0 += 1;
+        } else if choice_b.beats(choice_a) {
+            record.1 += 1;
+        } else {
+            record.2 += 1;
+        }
+    }
+
+    record
+}
+
+/// Pick a choice as per the `[Rock, Paper, Scissors]` weights, which needn't add up to 1.
+pub fn sample_choice(weights: &[f64; 3], rng: &mut impl Rng) -> Choice {
+    let mut pick = rng.gen::<f64>() * weights.iter().sum::<f64>();
+    for (choice, weight) in [Choice::Rock, Choice::Paper, Choice::Scissors]
+        .into_iter()
+        .zip(weights)
+    {
+        if pick < *weight {
+            return choice;
+        }
+        pick -= weight;
+    }
+
+    Choice::Scissors
+}
+
+/// A bot picking its moves at random as per the `[Rock, Paper, Scissors]` weights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedStrategy {
+    pub weights: [f64; 3],
+}
+
+impl WeightedStrategy {
+    /// A bot biased as per the weights of the choices, e.g. favoring Rock like many humans do.
+    /// The weights get normalized (to add up to 1), a choice left out is never played.
+    pub fn biased(weights: &HashMap<Choice, f64>) -> Result<Self, String> {
+        if let Some((choice, weight)) = weights
+            .iter()
+            .find(|(_, weight)| weight.is_nan() || **weight < 0.0)
+        {
+            return Err(format!(
+                "the weight of {choice} must not be negative, got {weight}"
+            ));
+        }
+        let total = weights.values().sum::<f64>();
+        if total <= 0.0 || total.is_infinite() {
+            return Err("at least a choice needs a (finite) positive weight".to_string());
+        }
+
+        let weight = |choice| weights.get(&choice).copied().unwrap_or(0.0) / total;
+        Ok(Self {
+            weights: [
+                weight(Choice::Rock),
+                weight(Choice::Paper),
+                weight(Choice::Scissors),
+            ],
+        })
+    }
+
+    /// The equilibrium mixed strategy of the classic ruleset: each choice beats one other & loses
+    /// to the last, so playing them a third of the time each is the only strategy no opponent
+    /// can exploit. Custom rulesets are out of scope: there's only the classic one.
+    pub fn equilibrium() -> Self {
+        Self {
+            weights: [1.0 / Choice::ALL.len() as f64; 3],
+        }
+    }
+
+    pub fn pick(&self, rng: &mut impl Rng) -> Choice {
+        sample_choice(&self.weights, rng)
+    }
+}
+
+/// A "clone" bot of the player `name`, playing as often each move as they did in `history`.
+/// A player who never played is cloned into a uniform bot.
+pub fn clone_strategy(history: &[Round], name: &str) -> WeightedStrategy {
+    let mut weights = [0.0; 3];
+    for choice in history.iter().filter_map(|round| round.players.get(name)) {
+        match choice {
+            Choice::Rock => weights[0] += 1.0,
+            Choice::Paper => weights[1] += 1.0,
+            Choice::Scissors => weights[2] += 1.0,
+        }
+    }
+    if weights == [0.0; 3] {
+        weights = [1.0; 3];
+    }
+
+    WeightedStrategy { weights }
+}
+
+/// Reports the progress of a long simulation, e.g. to show a progress bar.
+pub trait ProgressSink {
+    /// Called once before starting, with the number of ticks to expect.
+    fn start(&mut self, _total: u64) {}
+    fn tick(&mut self);
+    fn finish(&mut self) {}
+}
+
+/// No progress reported.
+impl ProgressSink for () {
+    fn tick(&mut self) {}
+}
+
+#[cfg(feature = "progress")]
+impl ProgressSink for indicatif::ProgressBar {
+    fn start(&mut self, total: u64) {
+        self.set_length(total);
+    }
+
+    fn tick(&mut self) {
+        self.inc(1);
+    }
+
+    fn finish(&mut self) {
+        indicatif::ProgressBar::finish(self);
+    }
+}
+
+/// Monte Carlo estimate of each player's average placement (1 = first) in a round-robin,
+/// given the players' estimated move distributions `[Rock, Paper, Scissors]`.
+/// Each trial, every pair of players plays one round and the winner gets a point.
+/// Players level on points share the better placement.
+/// Without any trial, there's nothing to estimate: the placements are empty.
+pub fn simulate_tournament(
+    players: &[(String, [f64; 3])],
+    trials: usize,
+    rng: &mut impl Rng,
+) -> HashMap<String, f64> {
+    simulate_tournament_with_progress(players, trials, rng, &mut ())
+}
+
+/// Same as `simulate_tournament`, reporting a tick to `progress` per match played (every pair of
+/// players, in every trial).
+pub fn simulate_tournament_with_progress(
+    players: &[(String, [f64; 3])],
+    trials: usize,
+    rng: &mut impl Rng,
+    progress: &mut impl ProgressSink,
+) -> HashMap<String, f64> {
+    let matches_per_trial = players.len() * players.len().saturating_sub(1) / 2;
+    progress.start((trials * matches_per_trial) as u64);
+    if trials == 0 {
+        progress.finish();
+        return HashMap::new();
+    }
+
+    let mut placements = vec![0usize; players.len()];
+    for _ in 0..trials {
+        let mut points = vec![0u32; players.len()];
+        for i in 0..players.len() {
+            for j in i + 1..players.len() {
+                let (name_i, name_j) = (&players[i].0, &players[j].0);
+                let players_scores = score_round(&[
+                    (
+                        name_i.clone(),
+                        RevealState::Revealed(sample_choice(&players[i].1, rng)),
+                    ),
+                    (
+                        name_j.clone(),
+                        RevealState::Revealed(sample_choice(&players[j].1, rng)),
+                    ),
+                ])
+                .expect("Simulated choices are all revealed");
+                match round_winner(&players_scores) {
+                    Some(winner) if &winner == name_i => points[i] += 1,
+                    Some(_) => points[j] += 1,
+                    None => {}
+                }
+                progress.tick();
+            }
+        }
+
+        for (i, placement) in placements.iter_mut().enumerate() {
+            *placement += 1 + points.iter().filter(|&&p| p > points[i]).count();
+        }
+    }
+
+    let placements = players
+        .iter()
+        .zip(placements)
+        .map(|((name, _), placement)| (name.clone(), placement as f64 / trials as f64))
+        .collect();
+    progress.finish();
+
+    placements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use proptest::prelude::*;
+    use std::cell::Cell;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    fn choice_strategy() -> impl Strategy<Value = Choice> {
+        prop_oneof![
+            Just(Choice::Rock),
+            Just(Choice::Paper),
+            Just(Choice::Scissors)
+        ]
+    }
+
+    type Players = Vec<(String, Choice)>;
+
+    /// Players (with unique names) & their choices, in listed order and in a shuffled order
+    fn reordered_players_strategy() -> impl Strategy<Value = (Players, Players)> {
+        prop::collection::vec(choice_strategy(), 2..8).prop_flat_map(|choices| {
+            let players = choices
+                .into_iter()
+                .enumerate()
+                .map(|(i, choice)| (format!("player{i}"), choice))
+                .collect::<Vec<_>>();
+            (Just(players.clone()), Just(players).prop_shuffle())
+        })
+    }
+
+    fn revealed(players: &Players) -> Vec<(String, RevealState)> {
+        players
+            .iter()
+            .map(|(name, choice)| (name.clone(), RevealState::Revealed(choice.clone())))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_score_round_symmetric_under_reordering((players, reordered) in reordered_players_strategy()) {
+            prop_assert_eq!(score_round(&revealed(&players)), score_round(&revealed(&reordered)));
+        }
+
+        #[test]
+        fn test_score_round_conserves_points((players, _) in reordered_players_strategy()) {
+            // each decisive pair awards exactly one point, ties none
+            let mut decisive_pairs = 0;
+            for i in 0..players.len() {
+                for j in i + 1..players.len() {
+                    if players[i].1.beats(&players[j].1) || players[j].1.beats(&players[i].1) {
+                        decisive_pairs += 1;
+                    }
+                }
+            }
+
+            prop_assert_eq!(score_round(&revealed(&players)).unwrap().values().sum::<u32>(), decisive_pairs);
+        }
+
+        #[test]
+        fn test_update_scores_matches_scoring_per_award((players, _) in reordered_players_strategy()) {
+            // the straightforward scoring, touching the map on every point awarded
+            let mut expected = HashMap::from([("Alice".to_string(), 3), ("Zed".to_string(), 1)]);
+            for i in 0..players.len() {
+                for j in i + 1..players.len() {
+                    if players[i].1.beats(&players[j].1) {
+                        *expected.entry(players[i].0.clone()).or_insert(0) += 1;
+                    } else if players[j].1.beats(&players[i].1) {
+                        *expected.entry(players[j].0.clone()).or_insert(0) += 1;
+                    }
+                }
+            }
+
+            let mut scores = HashMap::from([("Alice".to_string(), 3), ("Zed".to_string(), 1)]);
+            update_scores(&players, &mut scores);
+            prop_assert_eq!(scores, expected);
+        }
+    }
+
+    #[test]
+    fn test_score_every_round() {
+        let next = |choice: &Choice| {
+            Choice::ALL[(Choice::ALL.iter().position(|c| c == choice).unwrap() + 1) % 3].clone()
+        };
+
+        for players in [2, 3] {
+            let rounds = enumerate_rounds(players).collect::<Vec<_>>();
+            assert_eq!(rounds.len(), 3usize.pow(players as u32));
+            assert_eq!(
+                rounds
+                    .iter()
+                    .collect::<std::collections::HashSet<_>>()
+                    .len(),
+                rounds.len()
+            );
+
+            for choices in rounds {
+                let players = choices
+                    .iter()
+                    .enumerate()
+                    .map(|(i, choice)| (format!("player{i}"), choice.clone()))
+                    .collect::<Players>();
+                let scores = score_round(&revealed(&players)).unwrap();
+
+                // reordering the players, or swapping the choices cyclically (R -> P -> S -> R),
+                // doesn't change anyone's score
+                let mut reordered = players.clone();
+                reordered.reverse();
+                assert_eq!(score_round(&revealed(&reordered)).unwrap(), scores);
+                let rotated = players
+                    .iter()
+                    .map(|(name, choice)| (name.clone(), next(choice)))
+                    .collect::<Players>();
+                assert_eq!(score_round(&revealed(&rotated)).unwrap(), scores);
+
+                // each decisive pair awards exactly one point
+                let decisive_pairs = (0..players.len())
+                    .flat_map(|i| (i + 1..players.len()).map(move |j| (i, j)))
+                    .filter(|&(i, j)| choices[i] != choices[j])
+                    .count() as u32;
+                assert_eq!(scores.values().sum::<u32>(), decisive_pairs, "{choices:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_commit_blake3_256() {
+        let hash = commit_faster("Rock", "abhi");
+        assert_eq!(
+            hash,
+            hex!("4dfc91d264de6fa73305a7f9318cf1843575446ba36833df3177536d04d99ea7")
+        );
+    }
+
+    #[test]
+    fn test_beacon_from_commits() {
+        let commits = [("Rock", "alice"), ("Paper", "bob"), ("Rock", "carol")]
+            .map(|(choice, salt)| commit_faster(choice, salt));
+        let beacon = beacon_from_commits(&commits);
+
+        assert_eq!(beacon_from_commits(&commits), beacon);
+        let [alice, bob, carol] = commits;
+        assert_eq!(beacon_from_commits(&[carol, alice, bob]), beacon);
+
+        assert_ne!(
+            beacon_from_commits(&[alice, bob, commit_faster("Paper", "carol")]),
+            beacon
+        );
+        assert_ne!(beacon_from_commits(&[alice, bob]), beacon);
+    }
+
+    #[test]
+    fn test_parse_blake3_hex() {
+        let hash = commit_faster("Rock", "abhi");
+        assert_eq!(parse_blake3_hex(&hash.to_hex()), Ok(hash));
+        assert_eq!(
+            parse_blake3_hex("4dfc91d264"),
+            Err(HashParseError::WrongLength(10))
+        );
+        assert_eq!(
+            parse_blake3_hex(&format!("{hash}00")),
+            Err(HashParseError::WrongLength(66))
+        );
+        assert_eq!(
+            parse_blake3_hex("4dfc91d264").unwrap_err().to_string(),
+            "expected 64 hex chars (32 bytes), got 10"
+        );
+        let not_hex = "z".repeat(64);
+        assert_eq!(
+            parse_blake3_hex(&not_hex),
+            Err(HashParseError::InvalidHex(not_hex))
+        );
+
+        // the prompt asks again
+        let input = format!("4dfc91d264\n{hash}\n");
+        assert_eq!(
+            collect_commit(&mut std::io::Cursor::new(input), "commit:").unwrap(),
+            RevealState::Committed(hash)
+        );
+    }
+
+    #[test]
+    fn test_collect_input_closed() {
+        let err =
+            collect_input::<Choice>(&mut std::io::Cursor::new("Lizard\n"), "choice:").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        // the round ends with the input, rather than prompting forever
+        let input = format!("2\nAlice\n{}\n", commit_faster("Rock", "alice"));
+        let err = collect_round(
+            &mut std::io::Cursor::new(input),
+            &mut RoundOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_commit_padded() {
+        assert_eq!(
+            pad_commit_input("Rock", "abhi", 64).len(),
+            pad_commit_input("Scissors", "abhi", 64).len()
+        );
+        assert_eq!(pad_commit_input("Rock", "abhi", 64).len(), 64);
+        // a longer input spans more blocks
+        assert_eq!(pad_commit_input("Rock", &"s".repeat(60), 64).len(), 128);
+
+        let rock = commit_padded("Rock", "abhi", 64);
+        let scissors = commit_padded("Scissors", "abhi", 64);
+        assert!(reveal_padded(rock, "Rock", "abhi", 64));
+        assert!(reveal_padded(scissors, "Scissors", "abhi", 64));
+        assert!(!reveal_padded(rock, "Scissors", "abhi", 64));
+        // zeros ending the salt aren't mistaken for the padding
+        assert!(!reveal_padded(rock, "Rock", "abhi\0", 64));
+        assert_ne!(rock, commit_faster("Rock", "abhi"));
+    }
+
+    #[test]
+    fn test_collect_salt() {
+        let collect = |input: &str| collect_salt(&mut std::io::Cursor::new(input), "salt:", 8);
+
+        assert_eq!(
+            collect("abhi\n").unwrap(),
+            Ok(Answer::Value("abhi".to_string()))
+        );
+        assert_eq!(collect(":forfeit\r\n").unwrap(), Ok(Answer::Forfeit));
+        assert_eq!(
+            collect("saltsalt").unwrap(),
+            Ok(Answer::Value("saltsalt".to_string()))
+        );
+        // a byte over the cap, & way over it
+        assert_eq!(
+            collect("saltsalt!\n").unwrap(),
+            Err(GameError::SaltTooLarge { len: 9, max: 8 })
+        );
+        let mut input = std::io::Cursor::new(format!("{}\nabhi\n", "s".repeat(100)));
+        assert_eq!(
+            collect_salt(&mut input, "salt:", 8).unwrap(),
+            Err(GameError::SaltTooLarge { len: 100, max: 8 })
+        );
+        // the rest of the long line was skipped: the next one is read as is
+        assert_eq!(
+            collect_salt(&mut input, "salt:", 8).unwrap(),
+            Ok(Answer::Value("abhi".to_string()))
+        );
+        assert_eq!(
+            collect("").unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_commit_checked_salt_too_large() {
+        let salt = "s".repeat(65);
+        assert_eq!(
+            commit_checked("Rock", &salt, 64),
+            Err(GameError::SaltTooLarge { len: 65, max: 64 })
+        );
+        assert_eq!(
+            commit_checked("Rock", &salt[..64], 64),
+            Ok(commit_faster("Rock", &salt[..64]))
+        );
+
+        // and the reveal gets asked again
+        let input = format!(
+            "2\nAlice\n{}\nBob\n{}\nRock\n{salt}\nRock\nalice\nPaper\nbob\n",
+            commit_faster("Rock", "alice"),
+            commit_faster("Paper", "bob")
+        );
+        let mut options = RoundOptions {
+            max_salt_bytes: 64,
+            ..RoundOptions::default()
+        };
+        let players_details =
+            collect_round(&mut std::io::Cursor::new(input), &mut options).unwrap();
+        assert_eq!(players_details[0].1, RevealState::Revealed(Choice::Rock));
+    }
+
+    #[test]
+    fn test_reveal_blake3_256() {
+        let hash = commit_faster("rock", "abhi");
+        assert!(reveal_faster(hash, "rock", "abhi"));
+    }
+
+    #[test]
+    fn test_recommended_salt_bits() {
+        const YEAR: u64 = 365 * 24 * 60 * 60;
+        // a GPU doing a billion Blake3 hashes per second, for a year
+        assert_eq!(recommended_salt_bits(3, 1_000_000_000, YEAR), 57);
+
+        // longer target time or faster attacker => larger salt
+        let mut previous = 0;
+        for target_seconds in [1, 60, 3600, YEAR, 100 * YEAR] {
+            let bits = recommended_salt_bits(3, 1_000_000_000, target_seconds);
+            assert!(bits > previous);
+            previous = bits;
+        }
+        assert!(
+            recommended_salt_bits(3, 1_000_000_000_000, YEAR)
+                > recommended_salt_bits(3, 1_000_000_000, YEAR)
+        );
+
+        // more choices => larger salt
+        let mut previous = 0;
+        for num_choices in [2, 3, 5, 101] {
+            let bits = recommended_salt_bits(num_choices, 1_000_000_000, YEAR);
+            assert!(bits > previous);
+            previous = bits;
+        }
+        // & never a smaller one, step by step
+        for num_choices in 1..200 {
+            assert!(
+                recommended_salt_bits(num_choices + 1, 1_000_000_000, YEAR)
+                    >= recommended_salt_bits(num_choices, 1_000_000_000, YEAR)
+            );
+        }
+        for target_seconds in 1..200 {
+            assert!(
+                recommended_salt_bits(3, 1_000_000_000, target_seconds + 1)
+                    >= recommended_salt_bits(3, 1_000_000_000, target_seconds)
+            );
+        }
+
+        // nothing to defend against
+        assert_eq!(recommended_salt_bits(3, 0, YEAR), 0);
+    }
+
+    #[test]
+    fn test_salt_entropy_estimate() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let salts = (0..2000)
+            .map(|_| generate_salt(&mut rng))
+            .collect::<Vec<_>>();
+        assert!(salts.iter().all(|salt| salt.len() == 2 * SALT_BYTES));
+        // 4 bits per hex digit
+        let expected = (8 * SALT_BYTES) as f64;
+        let estimate = salt_entropy_estimate(&salts);
+        assert!((estimate - expected).abs() < 0.02 * expected, "{estimate}");
+
+        let constant = vec!["alice".to_string(); 2000];
+        assert!(salt_entropy_estimate(&constant).abs() < 1e-9);
+        assert_eq!(salt_entropy_estimate(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_reuse_risk() {
+        assert_eq!(reuse_risk(3, 1), 0.0);
+        // 2 commits with the same salt: same choice 1/3 of the time
+        assert!((reuse_risk(3, 2) - 1.0 / 3.0).abs() < 1e-9);
+
+        // increases with the reuse count
+        let risks = (1..10)
+            .map(|count| reuse_risk(3, count))
+            .collect::<Vec<_>>();
+        assert!(risks.windows(2).all(|pair| pair[0] < pair[1]), "{risks:?}");
+        assert!(risks.iter().all(|risk| (0.0..1.0).contains(risk)));
+
+        // decreases with more choices
+        let risks = (2..10)
+            .map(|choices| reuse_risk(choices, 4))
+            .collect::<Vec<_>>();
+        assert!(risks.windows(2).all(|pair| pair[0] > pair[1]), "{risks:?}");
+    }
+
+    #[test]
+    fn test_reveal_throttle() {
+        let mut throttle = RevealThrottle::new(Duration::from_secs(1));
+        let start = Instant::now();
+
+        assert!(throttle.allow("Alice", start));
+        // rapid repeated attempts are throttled
+        assert!(!throttle.allow("Alice", start + Duration::from_millis(10)));
+        assert!(!throttle.allow("Alice", start + Duration::from_millis(20)));
+        // per player
+        assert!(throttle.allow("Bob", start + Duration::from_millis(20)));
+        // and fine again once the player slows down
+        assert!(throttle.allow("Alice", start + Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn test_reveal_grace() {
+        let mut grace = RevealGrace::new(Duration::from_secs(30));
+        let start = Instant::now();
+
+        // the window starts with the first reveal, however late that is
+        assert!(grace.allow(start + Duration::from_secs(600)));
+        assert!(grace.allow(start + Duration::from_secs(630)));
+        // but not a reveal past it
+        assert!(!grace.allow(start + Duration::from_secs(631)));
+
+        assert!(!grace.expired(start + Duration::from_secs(630)));
+        assert!(grace.expired(start + Duration::from_secs(631)));
+        assert!(!RevealGrace::new(Duration::ZERO).expired(start));
+
+        // in a round, with a clock ticking 10s each time it's read
+        let mut players_details = [("Alice", "Rock"), ("Bob", "Paper"), ("Carol", "Scissors")]
+            .map(|(name, choice)| {
+                let commit_hash = commit_faster(choice, &name.to_lowercase());
+                (name.to_string(), RevealState::Committed(commit_hash))
+            })
+            .to_vec();
+        let mut ticks = 0;
+        let mut options = RoundOptions {
+            reveal_grace: Some(Duration::from_secs(30)),
+            clock: Box::new(move || {
+                ticks += 1;
+                start + Duration::from_secs(10 * ticks)
+            }),
+            ..RoundOptions::default()
+        };
+        let mut out = vec![];
+        // Alice reveals at 20s, Bob 20s later, & Carol still hasn't got it right 40s later
+        assert!(collect_reveals(
+            &mut std::io::Cursor::new("Rock\nalice\nPaper\nbob\nScissors\ncarl\n"),
+            &mut out,
+            &mut players_details,
+            &mut options,
+        )
+        .unwrap());
+
+        assert_eq!(players_details[1].1, RevealState::Revealed(Choice::Paper));
+        assert_eq!(
+            players_details[2].1,
+            RevealState::Forfeit { voluntary: false }
+        );
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .ends_with("The grace period is over, Carol forfeits the round.\n"));
+    }
+
+    #[test]
+    fn test_reveal_with_normalization_hint() {
+        let commit_hash = commit_faster("rock", "abhi");
+
+        let result = reveal_with_normalization_hint(commit_hash, "Rock", "abhi");
+        assert_eq!(
+            result,
+            RevealResult::SpellingMismatch {
+                committed: "rock".to_string()
+            }
+        );
+        assert_eq!(
+            result.to_string(),
+            "case/format mismatch: you committed a different spelling ('rock')"
+        );
+
+        assert_eq!(
+            reveal_with_normalization_hint(commit_hash, "rock", "abhi"),
+            RevealResult::Verified
+        );
+        // a different move, or salt
+        assert_eq!(
+            reveal_with_normalization_hint(commit_hash, "Paper", "abhi"),
+            RevealResult::Mismatch
+        );
+        assert_eq!(
+            reveal_with_normalization_hint(commit_hash, "Rock", "abhi2"),
+            RevealResult::Mismatch
+        );
+        // committed by the menu number
+        assert_eq!(
+            reveal_with_normalization_hint(commit_faster("2", "abhi"), "Paper", "abhi"),
+            RevealResult::SpellingMismatch {
+                committed: "2".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_designated_reveal() {
+        let salt = "alice-secret-salt";
+        let commit_hash = commit_faster("Paper", salt);
+
+        let public = designated_reveal(commit_hash, Choice::Paper, salt);
+        assert_eq!(public, (Choice::Paper, true));
+        assert!(!format!("{public:?}").contains(salt));
+
+        assert_eq!(
+            designated_reveal(commit_hash, Choice::Rock, salt),
+            (Choice::Rock, false)
+        );
+    }
+
+    #[test]
+    fn test_sign_transcript() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let transcript = "round 1: Alice=Rock, Bob=Scissors, winner=Alice";
+        let signature = sign_transcript(transcript, &key);
+
+        assert!(verify_transcript_signature(
+            transcript,
+            &signature,
+            &key.verifying_key()
+        ));
+
+        // one byte changed
+        let tampered = transcript.replace("Alice=Rock", "Alice=Sock");
+        assert!(!verify_transcript_signature(
+            tampered.as_str(),
+            &signature,
+            &key.verifying_key()
+        ));
+    }
+
+    #[test]
+    fn test_update_scores() {
+        // define a players details (from `sample.json` file)
+        let round_1 = [
+            ("Alice", "Rock", "alice"),
+            ("Bob", "Paper", "bob"),
+            ("Carol", "Scissors", "carol"),
+        ];
+        let round_2 = [
+            ("Alice", "Rock", "alice2"),
+            ("Bob", "Rock", "bob2"),
+            ("Carol", "Scissors", "carol2"),
+        ];
+
+        // define a players scores list
+        let mut players_scores = HashMap::<String, u32>::new();
+
+        for round in [round_1, round_2] {
+            let players_details = round
+                .iter()
+                .map(|&(name, choice, _salt)| (name.to_string(), choice.parse::<Choice>().unwrap()))
+                .collect::<Vec<_>>();
+            update_scores(&players_details, &mut players_scores);
+        }
+
+        // R,P,S: everyone beats someone. Then R,R,S: both Rock players beat Scissors.
+        assert_eq!(players_scores["Alice"], 2);
+        assert_eq!(players_scores["Bob"], 2);
+        assert_eq!(players_scores["Carol"], 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_play_round_from_fifo() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("rps-{}.fifo", std::process::id()));
+        let status = std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        // controller process driving the round, one frame (line) per prompt
+        let frames = format!(
+            "2\nAlice\n{}\nBob\n{}\nRock\nalice\nScissors\nbob\n",
+            commit_faster("Rock", "alice"),
+            commit_faster("Scissors", "bob")
+        );
+        let writer_path = path.clone();
+        let controller = std::thread::spawn(move || {
+            let mut fifo = std::fs::OpenOptions::new()
+                .write(true)
+                .open(writer_path)
+                .unwrap();
+            fifo.write_all(frames.as_bytes()).unwrap();
+        });
+
+        let fifo = File::open(&path).unwrap();
+        let players_scores =
+            play_round(&mut BufReader::new(fifo), &mut RoundOptions::default()).unwrap();
+        controller.join().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(players_scores["Alice"], 1);
+        assert_eq!(players_scores["Bob"], 0);
+    }
+
+    #[test]
+    fn test_record_result_concurrently() {
+        let store = Standings::default();
+        let games = (0..8)
+            .map(|game| {
+                let store = Arc::clone(&store);
+                std::thread::spawn(move || {
+                    for round in 0..1000 {
+                        let winner = if (game + round) % 2 == 0 {
+                            "Alice"
+                        } else {
+                            "Bob"
+                        };
+                        record_result(&store, winner);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for game in games {
+            game.join().unwrap();
+        }
+
+        let standings = store.lock().unwrap();
+        assert_eq!(standings.values().sum::<u32>(), 8 * 1000);
+        assert_eq!(standings["Alice"], 4000);
+        assert_eq!(standings["Bob"], 4000);
+    }
+
+    fn round(id: u32, players: &[(&str, Choice)]) -> Round {
+        Round {
+            id,
+            winner: None,
+            players: players
+                .iter()
+                .map(|(name, choice)| (name.to_string(), choice.clone()))
+                .collect(),
+            forfeits: HashMap::new(),
+            commit_times: HashMap::new(),
+            timestamp: id,
+        }
+    }
+
+    /// Alice & Bob's rivalry, with Carol joining in some rounds
+    fn rivalry_game() -> Game {
+        Game::from_rounds(
+            5,
+            vec![
+                round(1, &[("Alice", Choice::Rock), ("Bob", Choice::Scissors)]),
+                round(2, &[("Alice", Choice::Rock), ("Bob", Choice::Paper)]),
+                round(3, &[("Alice", Choice::Paper), ("Bob", Choice::Paper)]),
+                // Bob sat this one out
+                round(4, &[("Alice", Choice::Rock), ("Carol", Choice::Scissors)]),
+                round(
+                    5,
+                    &[
+                        ("Alice", Choice::Scissors),
+                        ("Bob", Choice::Paper),
+                        ("Carol", Choice::Rock),
+                    ],
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_record_scores() {
+        #[derive(Default)]
+        struct InMemoryStore {
+            scores: std::cell::RefCell<HashMap<String, u32>>,
+            saves: Cell<u32>,
+        }
+
+        impl ScoreStore for InMemoryStore {
+            fn load(&self) -> Result<HashMap<String, u32>, GameError> {
+                Ok(self.scores.borrow().clone())
+            }
+
+            fn save(&self, scores: &HashMap<String, u32>) -> Result<(), GameError> {
+                *self.scores.borrow_mut() = scores.clone();
+                self.saves.set(self.saves.get() + 1);
+                Ok(())
+            }
+        }
+
+        let store = InMemoryStore::default();
+        store.scores.borrow_mut().insert("Alice".to_string(), 3);
+        let round_scores = HashMap::from([("Alice".to_string(), 1), ("Bob".to_string(), 2)]);
+        let totals = record_scores(&store, &round_scores).unwrap();
+
+        let expected = HashMap::from([("Alice".to_string(), 4), ("Bob".to_string(), 2)]);
+        assert_eq!(totals, expected);
+        assert_eq!(*store.scores.borrow(), expected);
+        assert_eq!(store.saves.get(), 1);
+    }
+
+    #[test]
+    fn test_json_file_store() {
+        let path = std::env::temp_dir().join(format!("rps-scores-{}.json", std::process::id()));
+        let store = JsonFileStore { path: path.clone() };
+        let _ = std::fs::remove_file(&path);
+        assert!(store.load().unwrap().is_empty());
+
+        record_scores(&store, &HashMap::from([("Alice".to_string(), 2)])).unwrap();
+        let totals = record_scores(&store, &HashMap::from([("Alice".to_string(), 1)])).unwrap();
+        assert_eq!(totals["Alice"], 3);
+        assert_eq!(store.load().unwrap(), totals);
+
+        // a corrupted file is reported, not a panic
+        std::fs::write(&path, "{\"Alice\": ").unwrap();
+        assert!(matches!(store.load(), Err(GameError::Store(_))));
+        assert!(record_scores(&store, &totals).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_sqlite_store() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let players_details = [
+            ("Alice".to_string(), RevealState::Revealed(Choice::Rock)),
+            ("Bob".to_string(), RevealState::Revealed(Choice::Scissors)),
+            (
+                "Carol".to_string(),
+                RevealState::Forfeit { voluntary: true },
+            ),
+            (
+                "Dave".to_string(),
+                RevealState::Forfeit { voluntary: false },
+            ),
+        ];
+        let players_scores = score_round(&players_details).unwrap();
+        let mut round = Round::played(1, &players_details, &players_scores);
+        let mut details = round.players_details();
+        details.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(details, players_details);
+        round.commit_times = HashMap::from([("Alice".to_string(), Duration::from_millis(1500))]);
+        store.save_round(&round).unwrap();
+        record_scores(&store, &players_scores).unwrap();
+
+        assert_eq!(store.rounds().unwrap(), vec![round]);
+        assert_eq!(store.load().unwrap(), players_scores);
+        let alice: u32 = store
+            .conn
+            .query_row(
+                "SELECT score FROM scores WHERE player = 'Alice'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(alice, 3);
+
+        // the scores add up over the games
+        record_scores(&store, &HashMap::from([("Alice".to_string(), 1)])).unwrap();
+        let totals = store.load().unwrap();
+        assert_eq!(totals["Alice"], 4);
+        assert_eq!(totals["Bob"], 2);
+    }
+
+    #[test]
+    fn test_break_match_tie() {
+        let round = |id, winner: Option<&str>, alice_secs, bob_secs| Round {
+            id,
+            winner: winner.map(str::to_string),
+            players: HashMap::new(),
+            forfeits: HashMap::new(),
+            commit_times: HashMap::from([
+                ("Alice".to_string(), Duration::from_secs(alice_secs)),
+                ("Bob".to_string(), Duration::from_secs(bob_secs)),
+            ]),
+            timestamp: 0,
+        };
+        // tied on points: Bob won more rounds, Alice committed faster (12s vs 30s in total)
+        let game = Game::from_rounds(
+            3,
+            vec![
+                round(1, Some("Bob"), 4, 10),
+                round(2, Some("Bob"), 4, 10),
+                round(3, Some("Alice"), 4, 10),
+            ],
+        );
+        let tied = ["Alice".to_string(), "Bob".to_string()];
+
+        let tie_break = |tie_break, game| break_match_tie(tie_break, &tied, game);
+        assert_eq!(
+            tie_break(MatchTieBreak::FastestCommits, &game).as_deref(),
+            Some("Alice")
+        );
+        assert_eq!(
+            tie_break(MatchTieBreak::RoundsWon, &game).as_deref(),
+            Some("Bob")
+        );
+        assert_eq!(tie_break(MatchTieBreak::SuddenDeath, &game), None);
+        // still level
+        let same = Game::from_rounds(2, vec![round(1, None, 6, 2), round(2, None, 6, 10)]);
+        assert_eq!(tie_break(MatchTieBreak::FastestCommits, &same), None);
+        // Bob's commit times weren't measured
+        let mut unmeasured = game.clone();
+        for round in &mut unmeasured.round {
+            round.commit_times.remove("Bob");
+            round
+                .commit_times
+                .insert("Alice".to_string(), Duration::from_secs(60));
+        }
+        assert_eq!(
+            tie_break(MatchTieBreak::FastestCommits, &unmeasured).as_deref(),
+            Some("Alice")
+        );
+    }
+
+    #[test]
+    fn test_collect_round_commit_times() {
+        let input = format!(
+            "2\nAlice\n{}\nBob\n{}\nRock\nalice\nPaper\nbob\n",
+            commit_faster("Rock", "alice"),
+            commit_faster("Paper", "bob")
+        );
+        // each commit is read between two ticks of the clock
+        let start = Instant::now();
+        let mut ticks = 0;
+        let mut options = RoundOptions {
+            clock: Box::new(move || {
+                ticks += 1;
+                start + Duration::from_secs(ticks * ticks)
+            }),
+            ..RoundOptions::default()
+        };
+        collect_round(&mut std::io::Cursor::new(input), &mut options).unwrap();
+
+        assert_eq!(
+            options.commit_times,
+            HashMap::from([
+                ("Alice".to_string(), Duration::from_secs(4 - 1)),
+                ("Bob".to_string(), Duration::from_secs(16 - 9)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_resolve_round_without_absent_player() {
+        let registered = ["Alice", "Bob", "Carol"].map(String::from);
+        // Carol never committed
+        let players_details = vec![
+            ("Alice".to_string(), RevealState::Revealed(Choice::Rock)),
+            ("Bob".to_string(), RevealState::Revealed(Choice::Scissors)),
+        ];
+        let mut log = vec![];
+
+        let players_scores = resolve_round(&registered, &players_details, &mut log).unwrap();
+        assert_eq!(
+            players_scores,
+            HashMap::from([("Alice".to_string(), 1), ("Bob".to_string(), 0)])
+        );
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "Carol didn't commit, they sit this round out\n"
+        );
+
+        // Bob committed but never revealed: Alice wins on his forfeit
+        let players_details = vec![
+            ("Alice".to_string(), RevealState::Revealed(Choice::Rock)),
+            (
+                "Bob".to_string(),
+                RevealState::Committed(commit_faster("Paper", "bob")),
+            ),
+        ];
+        let mut log = vec![];
+        let players_scores = resolve_round(&registered, &players_details, &mut log).unwrap();
+        assert_eq!(
+            players_scores,
+            HashMap::from([("Alice".to_string(), 1), ("Bob".to_string(), 0)])
+        );
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "Carol didn't commit, they sit this round out\n\
+             Bob didn't reveal, they forfeit the round\n"
+        );
+    }
+
+    #[test]
+    fn test_run_headless() {
+        let mv = |round, player: &str, choice: Choice, salt: &str| Move {
+            round,
+            player: player.to_string(),
+            commit_hash: commit_faster(choice.as_str(), player),
+            choice,
+            salt: salt.to_string(),
+        };
+        let moves = vec![
+            mv(1, "Alice", Choice::Paper, "Alice"),
+            mv(1, "Bob", Choice::Rock, "Bob"),
+            mv(2, "Alice", Choice::Rock, "Alice"),
+            mv(2, "Bob", Choice::Paper, "Bob"),
+            mv(3, "Alice", Choice::Scissors, "Alice"),
+            // Bob got his salt wrong
+            mv(3, "Bob", Choice::Paper, "bob"),
+        ];
+
+        let HeadlessResult { result, failures } = run_headless(3, moves);
+        let winners = result
+            .game
+            .round
+            .iter()
+            .map(|round| (round.id, round.winner.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            winners,
+            [(1, Some("Alice")), (2, Some("Bob")), (3, Some("Alice"))]
+        );
+        assert_eq!(result.game.round[2].players.len(), 1);
+        assert_eq!(
+            result.game.round[2].forfeits,
+            HashMap::from([("Bob".to_string(), false)])
+        );
+        // Alice won round 3 on Bob's forfeit, not on her choice alone
+        assert_eq!(result.game.validate_consistency(), Ok(()));
+        assert_eq!(
+            result.scores,
+            HashMap::from([("Alice".to_string(), 2), ("Bob".to_string(), 1)])
+        );
+        assert_eq!(
+            failures,
+            [VerificationFailure {
+                round: 3,
+                player: "Bob".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_session() {
+        let revealed = |players: &[(&str, Choice)]| {
+            players
+                .iter()
+                .map(|(name, choice)| (name.to_string(), RevealState::Revealed(choice.clone())))
+                .collect::<Vec<_>>()
+        };
+
+        let mut session = Session::default();
+        session.add_game(1, 3);
+        session.add_game(2, 3);
+        let alice_bob = revealed(&[("Alice", Choice::Rock), ("Bob", Choice::Scissors)]);
+        session.record_round(1, &alice_bob).unwrap();
+        session.record_round(1, &alice_bob).unwrap();
+        session
+            .record_round(
+                2,
+                &revealed(&[("Alice", Choice::Rock), ("Carol", Choice::Paper)]),
+            )
+            .unwrap();
+        assert_eq!(
+            session.record_round(3, &alice_bob),
+            Err(GameError::UnknownGame(3))
+        );
+
+        let game_1 = &session.games[&1];
+        assert_eq!(game_1.game.round.len(), 2);
+        assert_eq!(game_1.game.round[1].id, 2);
+        assert_eq!(game_1.scores["Alice"], 2);
+        let game_2 = &session.games[&2];
+        assert_eq!(game_2.game.round.len(), 1);
+        assert_eq!(game_2.scores["Alice"], 0);
+        assert_eq!(game_2.game.round[0].winner.as_deref(), Some("Carol"));
+
+        let standings = session.combined_standings();
+        assert_eq!(standings["Alice"], 2);
+        assert_eq!(standings["Bob"], 0);
+        assert_eq!(standings["Carol"], 1);
+    }
+
+    #[test]
+    fn test_snapshot_if_due() {
+        #[derive(Default)]
+        struct RecordingStore {
+            saved_after: std::cell::RefCell<Vec<usize>>,
+        }
+
+        impl SnapshotStore for RecordingStore {
+            fn save_snapshot(&self, game: &Game) -> std::io::Result<()> {
+                self.saved_after.borrow_mut().push(game.round.len());
+                Ok(())
+            }
+        }
+
+        let rounds = rivalry_game().into_rounds();
+        let store = RecordingStore::default();
+        let mut game = Game::from_rounds(5, vec![]);
+        for round in &rounds {
+            game.round.push(round.clone());
+            snapshot_if_due(&game, NonZeroUsize::new(2).unwrap(), &store).unwrap();
+        }
+        assert_eq!(*store.saved_after.borrow(), [2, 4]);
+
+        // the game resumes from the file's snapshot
+        let path = std::env::temp_dir().join(format!("rps-snapshot-{}.jsonl", std::process::id()));
+        let file = JsonlSnapshotFile { path: path.clone() };
+        assert!(snapshot_if_due(&game, NonZeroUsize::new(5).unwrap(), &file).unwrap());
+        let resumed = read_round_log(BufReader::new(File::open(&path).unwrap())).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(resumed, rounds);
+    }
+
+    #[test]
+    fn test_push_round_evicts_to_log() {
+        let round = |id| Round {
+            id,
+            winner: (id % 2 == 0).then(|| "Alice".to_string()),
+            players: HashMap::from([
+                ("Alice".to_string(), Choice::Rock),
+                (
+                    "Bob".to_string(),
+                    if id % 2 == 0 {
+                        Choice::Scissors
+                    } else {
+                        Choice::Rock
+                    },
+                ),
+            ]),
+            // conceded the even rounds, ran out of time in the odd ones
+            forfeits: HashMap::from([("Carol".to_string(), id % 2 == 0)]),
+            commit_times: HashMap::from([("Bob".to_string(), Duration::from_millis(250))]),
+            timestamp: 1_700_000_000 + id,
+        };
+        let mut game = Game::from_rounds(10, vec![]);
+        let mut log = vec![];
+        for id in 1..=5 {
+            game.push_round(round(id), NonZeroUsize::new(3), &mut log)
+                .unwrap();
+        }
+
+        assert_eq!(game.round.len(), 3);
+        assert_eq!(game.round, (3..=5).map(round).collect::<Vec<_>>());
+        assert_eq!(read_round_log(&log[..]).unwrap(), vec![round(1), round(2)]);
+
+        // uncapped, nothing gets evicted
+        game.push_round(round(6), None, &mut log).unwrap();
+        assert_eq!(game.round.len(), 4);
+        assert_eq!(read_round_log(&log[..]).unwrap().len(), 2);
+        assert!(read_round_log(&b"{\"id\": 1}\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_player_names_and_score_of() {
+        let game = rivalry_game();
+        assert_eq!(game.player_names(), ["Alice", "Bob", "Carol"]);
+        assert!(Game::from_rounds(3, vec![]).player_names().is_empty());
+
+        let scores = HashMap::from([("Alice".to_string(), 4), ("Bob".to_string(), 0)]);
+        assert_eq!(score_of(&scores, "Alice"), 4);
+        assert_eq!(score_of(&scores, "Bob"), 0);
+        assert_eq!(score_of(&scores, "Zed"), 0);
+    }
+
+    #[test]
+    fn test_replay_whatif() {
+        let game = rivalry_game();
+        // Bob won round 2 with Paper against Rock: what if he had played Scissors?
+        let whatif = replay_whatif(&game, 2, "Bob", Choice::Scissors).unwrap();
+
+        assert_eq!(whatif.game.round[1].winner.as_deref(), Some("Alice"));
+        assert_eq!(whatif.game.round[1].players["Bob"], Choice::Scissors);
+        // the other rounds are as played
+        assert_eq!(whatif.game.round[0].players, game.round[0].players);
+        assert_eq!(whatif.game.round[0].winner.as_deref(), Some("Alice"));
+        assert_eq!(
+            whatif.scores,
+            HashMap::from([
+                ("Alice".to_string(), 4),
+                ("Bob".to_string(), 1),
+                ("Carol".to_string(), 1),
+            ])
+        );
+
+        assert_eq!(
+            replay_whatif(&game, 9, "Bob", Choice::Rock).err(),
+            Some(GameError::RoundOutOfRange {
+                id: 9,
+                total_rounds: 5
+            })
+        );
+        // Carol didn't play round 1
+        assert_eq!(
+            replay_whatif(&game, 1, "Carol", Choice::Rock).err(),
+            Some(GameError::NotInRound("Carol".to_string()))
+        );
+
+        // Bob forfeited round 1: what if he had revealed Paper?
+        let mut forfeited = rivalry_game();
+        forfeited.round[0].players.remove("Bob");
+        forfeited.round[0].forfeits.insert("Bob".to_string(), false);
+        let whatif = replay_whatif(&forfeited, 1, "Bob", Choice::Paper).unwrap();
+        assert!(whatif.game.round[0].forfeits.is_empty());
+        assert_eq!(whatif.game.round[0].players["Bob"], Choice::Paper);
+        assert_eq!(whatif.game.round[0].winner.as_deref(), Some("Bob"));
+    }
+
+    #[test]
+    fn test_merge_histories() {
+        let rounds = rivalry_game().into_rounds();
+        // both nodes saw round 3
+        let a = Game::from_rounds(5, vec![rounds[0].clone(), rounds[2].clone()]);
+        let b = Game::from_rounds(5, rounds[2..].to_vec());
+        let merged = merge_histories(&a, &b).unwrap();
+        assert_eq!(merged.total_rounds, 5);
+        assert_eq!(
+            merged
+                .into_rounds()
+                .iter()
+                .map(|round| round.id)
+                .collect::<Vec<_>>(),
+            [1, 3, 4, 5]
+        );
+
+        let mut conflicting = rounds[2].clone();
+        conflicting.players.insert("Bob".to_string(), Choice::Rock);
+        let b = Game::from_rounds(5, vec![conflicting]);
+        assert_eq!(
+            merge_histories(&a, &b).unwrap_err(),
+            MergeConflict { id: 3 }
+        );
+
+        // Carol forfeited on both nodes, but conceded on only one
+        let (mut conceded, mut timed_out) = (rounds[0].clone(), rounds[0].clone());
+        conceded.forfeits.insert("Carol".to_string(), true);
+        timed_out.forfeits.insert("Carol".to_string(), false);
+        assert_eq!(
+            merge_histories(
+                &Game::from_rounds(5, vec![conceded]),
+                &Game::from_rounds(5, vec![timed_out])
+            )
+            .unwrap_err(),
+            MergeConflict { id: 1 }
+        );
+    }
+
+    #[test]
+    fn test_validate_consistency() {
+        let mut game = rivalry_game();
+        for round in &mut game.round {
+            round.winner = round_winner(&round.rescore());
+        }
+        assert_eq!(game.validate_consistency(), Ok(()));
+
+        // Bob's Paper beat Alice's Rock in round 2
+        let mut wrong_winner = game.clone();
+        wrong_winner.round[1].winner = Some("Alice".to_string());
+        assert_eq!(
+            wrong_winner.validate_consistency(),
+            Err(vec![GameError::WrongWinner {
+                id: 2,
+                winner: Some("Alice".to_string())
+            }])
+        );
+        wrong_winner.round[1].winner = None;
+        assert_eq!(
+            wrong_winner.validate_consistency(),
+            Err(vec![GameError::WrongWinner {
+                id: 2,
+                winner: None
+            }])
+        );
+
+        // a round recorded twice, one past the last round & one without players
+        let mut rounds = game.into_rounds();
+        rounds.push(rounds[2].clone());
+        rounds.push(round(6, &[]));
+        let corrupted = Game::from_rounds(5, rounds);
+        assert_eq!(
+            corrupted.validate_consistency(),
+            Err(vec![
+                GameError::DuplicateRound(3),
+                GameError::RoundOutOfRange {
+                    id: 6,
+                    total_rounds: 5
+                },
+                GameError::EmptyRound(6),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_to_scoreboard_payload() {
+        let scores = HashMap::from([
+            ("Alice".to_string(), 3),
+            ("Bob".to_string(), 1),
+            ("Carol".to_string(), 3),
+            ("Dave".to_string(), 0),
+        ]);
+        let payload = to_scoreboard_payload(&scores, "game-42");
+
+        assert_eq!(payload["game_id"], "game-42");
+        assert!(payload["timestamp"].as_u64().unwrap() > 0);
+        assert_eq!(
+            payload["scores"],
+            serde_json::json!([
+                { "player": "Alice", "score": 3, "rank": 1 },
+                { "player": "Carol", "score": 3, "rank": 1 },
+                { "player": "Bob", "score": 1, "rank": 3 },
+                { "player": "Dave", "score": 0, "rank": 4 },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_render_html() {
+        let game = rivalry_game();
+        let scores = HashMap::from([
+            ("Alice".to_string(), 3),
+            ("Bob".to_string(), 2),
+            ("Carol".to_string(), 1),
+        ]);
+        let html = render_html(&GameResult { game, scores });
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>Alice wins!</h1>"));
+        // header + a row per round
+        assert_eq!(html.matches("<tr>").count(), 1 + 5);
+        assert!(
+            html.contains("<tr><td>4</td><td>Alice: Rock, Carol: Scissors</td><td>Tie</td></tr>")
+        );
+        assert!(html.contains("<svg"));
+        // Rock is the most played choice
+        assert!(html.contains("<rect x=\"80\" y=\"0\" width=\"200\""));
+    }
+
+    #[test]
+    fn test_result_card_svg() {
+        let scores = HashMap::from([("Alice".to_string(), 3), ("Bob".to_string(), 2)]);
+        let svg = result_card_svg(&rivalry_game(), &scores);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<text").count(), svg.matches("</text>").count());
+        assert!(svg.contains("Alice wins!"));
+        assert!(svg.contains(">Alice: 3<"));
+        assert!(svg.contains(">Bob: 2<"));
+    }
+
+    #[test]
+    fn test_game_from_into_rounds() {
+        let rounds = vec![
+            round(1, &[("Alice", Choice::Rock), ("Bob", Choice::Scissors)]),
+            round(2, &[("Alice", Choice::Paper), ("Bob", Choice::Paper)]),
+        ];
+        let game = Game::from_rounds(2, rounds.clone());
+
+        assert_eq!(game.total_rounds, 2);
+        assert_eq!(game.into_rounds(), rounds);
+    }
+
+    #[test]
+    fn test_scores_plausible() {
+        let game = rivalry_game();
+        let scores = |list: &[(&str, u32)]| {
+            list.iter()
+                .map(|(name, score)| (name.to_string(), *score))
+                .collect::<HashMap<_, _>>()
+        };
+
+        // what the history actually gives
+        assert!(scores_plausible(
+            &game,
+            &scores(&[("Alice", 3), ("Bob", 2), ("Carol", 1)])
+        ));
+        // Carol played 2 rounds, against at most 2 opponents
+        assert!(scores_plausible(&game, &scores(&[("Carol", 3)])));
+        assert!(!scores_plausible(&game, &scores(&[("Carol", 4)])));
+        // Dave never played
+        assert!(!scores_plausible(&game, &scores(&[("Dave", 1)])));
+
+        // Alice beats Bob & gets a point for Carol's forfeit, Carol gets nothing
+        let forfeited = Game::from_rounds(
+            1,
+            vec![Round {
+                forfeits: HashMap::from([("Carol".to_string(), false)]),
+                ..round(1, &[("Alice", Choice::Rock), ("Bob", Choice::Scissors)])
+            }],
+        );
+        assert!(scores_plausible(&forfeited, &scores(&[("Alice", 2)])));
+        assert!(!scores_plausible(&forfeited, &scores(&[("Alice", 3)])));
+        assert!(!scores_plausible(&forfeited, &scores(&[("Carol", 1)])));
+    }
+
+    #[test]
+    fn test_score_timeline() {
+        let game = Game::from_rounds(
+            4,
+            vec![
+                round(1, &[("Alice", Choice::Rock), ("Bob", Choice::Scissors)]),
+                round(2, &[("Alice", Choice::Rock), ("Bob", Choice::Paper)]),
+                round(
+                    3,
+                    &[
+                        ("Alice", Choice::Paper),
+                        ("Bob", Choice::Scissors),
+                        ("Carol", Choice::Rock),
+                    ],
+                ),
+                // a draw, but Carol didn't reveal
+                Round {
+                    forfeits: HashMap::from([("Carol".to_string(), false)]),
+                    ..round(4, &[("Alice", Choice::Rock), ("Bob", Choice::Rock)])
+                },
+            ],
+        );
+        let timeline = score_timeline(&game);
+
+        assert_eq!(timeline["Alice"], vec![1, 1, 2, 3]);
+        assert_eq!(timeline["Bob"], vec![0, 1, 2, 3]);
+        assert_eq!(timeline["Carol"], vec![0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_rounds_with() {
+        let game = rivalry_game();
+
+        let ids = |rounds: Vec<&Round>| rounds.iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids(rounds_with(&game, "Alice", "Bob")), vec![1, 2, 3, 5]);
+        assert_eq!(ids(rounds_with(&game, "Carol", "Alice")), vec![4, 5]);
+        assert_eq!(ids(rounds_with(&game, "Bob", "Dave")), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_matchmake() {
+        let mut queue = vec!["Alice".to_string(), "Bob".to_string(), "Carol".to_string()];
+
+        assert_eq!(
+            matchmake(&mut queue),
+            Some(("Alice".to_string(), "Bob".to_string()))
+        );
+        assert_eq!(queue, vec!["Carol".to_string()]);
+        assert_eq!(matchmake(&mut queue), None);
+        assert_eq!(queue, vec!["Carol".to_string()]);
+    }
+
+    #[test]
+    fn test_predictability() {
+        use Choice::*;
+
+        assert_eq!(predictability(&vec![Rock; 10]), 1.0);
+        assert_eq!(predictability(&[Rock]), 0.0);
+
+        let balanced = [
+            Rock, Paper, Scissors, Paper, Rock, Scissors, Scissors, Rock, Paper, Rock, Scissors,
+            Paper,
+        ];
+        assert!(
+            predictability(&balanced) < 0.4,
+            "{}",
+            predictability(&balanced)
+        );
+        // repeating now & then is caught by the "repeats" predictor
+        let streaky = [
+            Rock, Rock, Rock, Paper, Paper, Paper, Scissors, Scissors, Scissors,
+        ];
+        assert!(predictability(&streaky) > predictability(&balanced));
+        // always countering their last move is only caught by the "counter" predictor
+        let cycling = Choice::ALL
+            .iter()
+            .cycle()
+            .take(12)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(predictability(&cycling), 1.0);
+    }
+
+    #[test]
+    fn test_update_elo() {
+        let gain = |loser_rating: f64| {
+            let mut ratings = HashMap::from([
+                ("Alice".to_string(), 1500.0),
+                ("Bob".to_string(), loser_rating),
+            ]);
+            update_elo(&mut ratings, "Alice", "Bob");
+            // zero-sum
+            assert!((ratings["Alice"] + ratings["Bob"] - 1500.0 - loser_rating).abs() < 1e-9);
+            ratings["Alice"] - 1500.0
+        };
+        assert!(gain(1700.0) > gain(1500.0));
+        assert!(gain(1500.0) > gain(1300.0));
+        assert!((gain(1500.0) - ELO_K_FACTOR / 2.0).abs() < 1e-9);
+
+        // unrated players start at the initial rating, a tie between equals changes nothing
+        let mut ratings = HashMap::new();
+        update_elo_with(&mut ratings, "Alice", "Bob", 0.5, 16.0);
+        assert_eq!(ratings["Alice"], ELO_INITIAL_RATING);
+        assert_eq!(ratings["Bob"], ELO_INITIAL_RATING);
+        // a tie against a stronger player still gains rating
+        ratings.insert("Bob".to_string(), 1700.0);
+        update_elo_with(&mut ratings, "Alice", "Bob", 0.5, 16.0);
+        assert!(ratings["Alice"] > ELO_INITIAL_RATING);
+    }
+
+    #[test]
+    fn test_choice_win_rates() {
+        let mut game = rivalry_game();
+        for (round, winner) in game.round.iter_mut().zip(["Alice", "Bob", "", "Alice", ""]) {
+            round.winner = Some(winner.to_string()).filter(|winner| !winner.is_empty());
+        }
+
+        let rates = choice_win_rates(&game);
+        // Rock won 2 of the 4 times played (Alice's in rounds 1 & 4), Paper 1 of 4 (Bob's in
+        // round 2), Scissors none of 3
+        assert_eq!(rates[&Choice::Rock], 2.0 / 4.0);
+        assert_eq!(rates[&Choice::Paper], 1.0 / 4.0);
+        assert_eq!(rates[&Choice::Scissors], 0.0);
+
+        assert!(choice_win_rates(&Game::from_rounds(1, vec![])).is_empty());
+    }
+
+    #[test]
+    fn test_head_to_head() {
+        let game = rivalry_game();
+
+        assert_eq!(head_to_head(&game, "Alice", "Bob"), (2, 1, 1));
+        assert_eq!(head_to_head(&game, "Bob", "Alice"), (1, 2, 1));
+        assert_eq!(head_to_head(&game, "Alice", "Dave"), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_simulate_tournament() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let third = 1.0 / 3.0;
+        let players = [
+            ("Rocky".to_string(), [0.8, 0.1, 0.1]),
+            // counters the Rock-heavy player, and no one can exploit the uniform player
+            ("Papery".to_string(), [0.1, 0.8, 0.1]),
+            ("Uniform".to_string(), [third, third, third]),
+        ];
+        let placements = simulate_tournament(&players, 10_000, &mut StdRng::seed_from_u64(42));
+
+        assert!(
+            placements["Papery"] < placements["Uniform"],
+            "{placements:?}"
+        );
+        assert!(
+            placements["Uniform"] < placements["Rocky"],
+            "{placements:?}"
+        );
+    }
+
+    #[test]
+    fn test_simulate_tournament_progress() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        #[derive(Default)]
+        struct Ticks {
+            total: u64,
+            ticks: u64,
+            finished: bool,
+        }
+
+        impl ProgressSink for Ticks {
+            fn start(&mut self, total: u64) {
+                self.total = total;
+            }
+
+            fn tick(&mut self) {
+                self.ticks += 1;
+            }
+
+            fn finish(&mut self) {
+                self.finished = true;
+            }
+        }
+
+        let players =
+            ["Alice", "Bob", "Carol", "Dave"].map(|name| (name.to_string(), [0.5, 0.2, 0.3]));
+        let mut progress = Ticks::default();
+        simulate_tournament_with_progress(
+            &players,
+            10,
+            &mut StdRng::seed_from_u64(42),
+            &mut progress,
+        );
+
+        // 6 matches (pairs) per trial
+        assert_eq!(progress.total, 60);
+        assert_eq!(progress.ticks, 60);
+        assert!(progress.finished);
+
+        let mut progress = Ticks::default();
+        let placements = simulate_tournament_with_progress(
+            &players,
+            0,
+            &mut StdRng::seed_from_u64(42),
+            &mut progress,
+        );
+        assert!(placements.is_empty());
+        assert_eq!(progress.total, 0);
+        assert!(progress.finished);
+    }
+
+    #[test]
+    fn test_equilibrium_strategy() {
+        let equilibrium = WeightedStrategy::equilibrium();
+        assert_eq!(equilibrium.weights, [1.0 / 3.0; 3]);
+
+        // no choice expects to win (or lose) against it
+        for choice in Choice::ALL {
+            let expected_payoff = Choice::ALL
+                .iter()
+                .zip(equilibrium.weights)
+                .map(|(other, weight)| {
+                    if choice.beats(other) {
+                        weight
+                    } else if other.beats(&choice) {
+                        -weight
+                    } else {
+                        0.0
+                    }
+                })
+                .sum::<f64>();
+            assert!(expected_payoff.abs() < 1e-12, "{choice}: {expected_payoff}");
+        }
+    }
+
+    #[test]
+    fn test_biased_strategy() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let bot = WeightedStrategy::biased(&HashMap::from([
+            (Choice::Rock, 8.0),
+            (Choice::Paper, 1.0),
+            (Choice::Scissors, 1.0),
+        ]))
+        .unwrap();
+        assert_eq!(bot.weights, [0.8, 0.1, 0.1]);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut counts = HashMap::<Choice, u32>::new();
+        for _ in 0..1000 {
+            *counts.entry(bot.pick(&mut rng)).or_insert(0) += 1;
+        }
+        assert!(
+            counts[&Choice::Rock] > 3 * counts[&Choice::Paper],
+            "{counts:?}"
+        );
+        assert!(
+            counts[&Choice::Rock] > 3 * counts[&Choice::Scissors],
+            "{counts:?}"
+        );
+
+        // a left out choice is never played
+        let bot = WeightedStrategy::biased(&HashMap::from([(Choice::Paper, 2.0)])).unwrap();
+        assert_eq!(bot.weights, [0.0, 1.0, 0.0]);
+
+        assert!(WeightedStrategy::biased(&HashMap::from([
+            (Choice::Rock, 2.0),
+            (Choice::Paper, -1.0)
+        ]))
+        .is_err());
+        assert!(WeightedStrategy::biased(&HashMap::from([(Choice::Rock, f64::NAN)])).is_err());
+        assert!(WeightedStrategy::biased(&HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_clone_strategy() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        // 80% Rock
+        let history = (1..=10)
+            .map(|id| {
+                let choice = match id {
+                    1 => Choice::Paper,
+                    2 => Choice::Scissors,
+                    _ => Choice::Rock,
+                };
+                round(id, &[("Rocky", choice), ("Bob", Choice::Paper)])
+            })
+            .collect::<Vec<_>>();
+        let bot = clone_strategy(&history, "Rocky");
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut counts = HashMap::<&str, u32>::new();
+        for _ in 0..1000 {
+            *counts.entry(bot.pick(&mut rng).as_str()).or_insert(0) += 1;
+        }
+        assert!(counts["Rock"] > 3 * counts["Paper"], "{counts:?}");
+        assert!(counts["Rock"] > 3 * counts["Scissors"], "{counts:?}");
+
+        assert_eq!(clone_strategy(&history, "Carol").weights, [1.0; 3]);
+    }
+
+    #[test]
+    fn test_beats_every_pair() {
+        use Choice::*;
+
+        // (a, b, a beats b): R < P < S < R
+        let expected = [
+            (Rock, Rock, false),
+            (Rock, Paper, false),
+            (Rock, Scissors, true),
+            (Paper, Rock, true),
+            (Paper, Paper, false),
+            (Paper, Scissors, false),
+            (Scissors, Rock, false),
+            (Scissors, Paper, true),
+            (Scissors, Scissors, false),
+        ];
+        for (a, b, a_beats_b) in expected {
+            assert_eq!(a.beats(&b), a_beats_b, "{a:?} vs {b:?}");
+        }
+
+        // cyclic: each choice beats exactly one other & is beaten by exactly one other
+        let choices = [Rock, Paper, Scissors];
+        for a in &choices {
+            assert_eq!(choices.iter().filter(|b| a.beats(b)).count(), 1);
+            assert_eq!(choices.iter().filter(|b| b.beats(a)).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_choice_is_not_ordered() {
+        // `Choice` mustn't be (Partial)Ord: any linear order of R, P, S breaks the cycle,
+        // which is how Scissors used to beat Rock when listed first.
+        // Checked at compile time: the inherent const only applies when `T: PartialOrd`, otherwise
+        // the trait's one does.
+        trait NotPartialOrd {
+            const IMPLEMENTED: bool = false;
+        }
+        impl<T> NotPartialOrd for T {}
+        struct Probe<T>(std::marker::PhantomData<T>);
+        impl<T: PartialOrd> Probe<T> {
+            const IMPLEMENTED: bool = true;
+        }
+
+        const { assert!(Probe::<u32>::IMPLEMENTED) };
+        const { assert!(!Probe::<Choice>::IMPLEMENTED) };
+    }
+
+    #[test]
+    fn test_parse_choice() {
+        assert_eq!("2".parse::<Choice>(), Ok(Choice::Paper));
+        assert_eq!("1".parse::<Choice>(), Ok(Choice::Rock));
+        assert_eq!("Scissors".parse::<Choice>(), Ok(Choice::Scissors));
+        assert!("4".parse::<Choice>().is_err());
+        assert!("Lizard".parse::<Choice>().is_err());
+    }
+
+    #[test]
+    fn test_estimate_security_bits() {
+        let config = |salt_bits| CommitConfig {
+            algo: HashAlgo::Blake3,
+            salt_bits,
+            num_choices: 3,
+        };
+
+        // a 1-byte salt: 3 * 256 guesses
+        let short = estimate_security_bits(&config(8));
+        assert!((short - 768f64.log2()).abs() < 1e-9);
+        let long = estimate_security_bits(&config(8 * SALT_BYTES));
+        assert!(long > 10.0 * short, "{short} vs {long}");
+
+        // the salt can't make up for the hash
+        assert_eq!(estimate_security_bits(&config(1024)), 128.0);
+        let sha3 = CommitConfig {
+            algo: HashAlgo::Sha3,
+            ..config(1024)
+        };
+        assert_eq!(estimate_security_bits(&sha3), 256.0);
+    }
+
+    #[test]
+    fn test_check_commit() {
+        let sha3 = HashAlgo::Sha3.commit("Rock", "abhi");
+        assert_ne!(sha3, HashAlgo::Blake3.commit("Rock", "abhi"));
+        assert_eq!(
+            HashAlgo::Blake3.commit("Rock", "abhi"),
+            *commit_faster("Rock", "abhi").as_bytes()
+        );
+
+        assert_eq!(
+            check_commit(HashAlgo::Sha3, &sha3, "Rock", "abhi"),
+            CommitCheck::Match
+        );
+        assert_eq!(
+            check_commit(HashAlgo::Sha3, &sha3, "Paper", "abhi"),
+            CommitCheck::NoMatch
+        );
+        // a SHA3 commit checked as Blake3
+        let check = check_commit(HashAlgo::Blake3, &sha3, "Rock", "abhi");
+        assert_eq!(check, CommitCheck::OtherAlgo(HashAlgo::Sha3));
+        assert_eq!(
+            check.to_string(),
+            "NO MATCH: This commit doesn't match the selected algorithm; try --algo sha3"
+        );
+    }
+
+    #[test]
+    fn test_assert_uniform_scheme() {
+        let tagged = |scheme, choice, salt| TaggedCommit {
+            scheme,
+            digest: *commit_faster(choice, salt).as_bytes(),
+        };
+
+        let uniform = [
+            tagged(HashAlgo::Blake3, "Rock", "alice"),
+            tagged(HashAlgo::Blake3, "Paper", "bob"),
+        ];
+        assert_eq!(assert_uniform_scheme(&uniform), Ok(()));
+        assert_eq!(assert_uniform_scheme(&[]), Ok(()));
+
+        let mixed = [
+            tagged(HashAlgo::Blake3, "Rock", "alice"),
+            tagged(HashAlgo::Blake3, "Paper", "bob"),
+            tagged(HashAlgo::Sha3, "Scissors", "carol"),
+        ];
+        let mismatch = assert_uniform_scheme(&mixed).unwrap_err();
+        assert_eq!(
+            mismatch,
+            SchemeMismatch {
+                expected: HashAlgo::Blake3,
+                found: HashAlgo::Sha3,
+                index: 2
+            }
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            "commit #3 uses Sha3 but the round uses Blake3"
+        );
+    }
+
+    #[test]
+    fn test_parse_hash_algo() {
+        assert_eq!("blake3".parse::<HashAlgo>(), Ok(HashAlgo::Blake3));
+        assert_eq!("SHA3".parse::<HashAlgo>(), Ok(HashAlgo::Sha3));
+        assert_eq!(
+            "md5".parse::<HashAlgo>(),
+            Err("unknown hash algorithm 'md5', expected one of: blake3, sha3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_play_round_numeric_reveal() {
+        let input = format!(
+            "2\nAlice\n{}\nBob\n{}\n2\nalice\nRock\nbob\n",
+            commit_faster("Paper", "alice"),
+            commit_faster(Choice::Rock.as_str(), "bob")
+        );
+        let players_scores = play_round(
+            &mut std::io::Cursor::new(input),
+            &mut RoundOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(players_scores["Alice"], 1);
+        assert_eq!(players_scores["Bob"], 0);
+    }
+
+    #[test]
+    fn test_voluntary_forfeit() {
+        // Bob concedes when asked to reveal, Carol right away at the commit
+        let input = format!(
+            "3\nAlice\n{}\nBob\n{}\nCarol\n:forfeit\nRock\nalice\n:forfeit\n",
+            commit_faster("Rock", "alice"),
+            commit_faster("Paper", "bob")
+        );
+        let players_details = collect_round(
+            &mut std::io::Cursor::new(input.clone()),
+            &mut RoundOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            players_details,
+            vec![
+                ("Alice".to_string(), RevealState::Revealed(Choice::Rock)),
+                ("Bob".to_string(), RevealState::Forfeit { voluntary: true }),
+                (
+                    "Carol".to_string(),
+                    RevealState::Forfeit { voluntary: true }
+                ),
+            ]
+        );
+
+        // Alice wins against both forfeits, even though Bob had committed Paper
+        let players_scores = play_round(
+            &mut std::io::Cursor::new(input),
+            &mut RoundOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(players_scores["Alice"], 2);
+        assert_eq!(players_scores["Bob"], 0);
+        assert_eq!(players_scores["Carol"], 0);
+    }
+
+    #[test]
+    fn test_timed_lines() {
+        /// Produces no input until unblocked, like a player who walked away: then it's closed.
+        struct Silent(std::sync::mpsc::Receiver<()>);
+
+        impl std::io::Read for Silent {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                // only ends when the sender is dropped
+                let _ = self.0.recv();
+                Ok(0)
+            }
+        }
+
+        let (unblock, blocked) = std::sync::mpsc::channel();
+        let mut silent =
+            TimedLines::new(BufReader::new(Silent(blocked)), Duration::from_millis(20));
+        let started = Instant::now();
+        for _ in 0..3 {
+            assert!(matches!(silent.next_line(), Err(InputError::TimedOut)));
+        }
+        // a player who doesn't commit in time forfeits
+        assert_eq!(
+            collect_commit(&mut silent, "commit: ").unwrap(),
+            RevealState::Forfeit { voluntary: false }
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        drop(unblock);
+        assert!(matches!(silent.next_line(), Err(InputError::Closed)));
+
+        let mut lines = TimedLines::new(
+            std::io::Cursor::new("Rock\nalice\n"),
+            Duration::from_secs(5),
+        );
+        assert_eq!(lines.next_line().unwrap(), "Rock");
+        let mut line = String::new();
+        lines.read_line(&mut line).unwrap();
+        assert_eq!(line, "alice\n");
+        assert!(matches!(lines.next_line(), Err(InputError::Closed)));
+        assert_eq!(lines.read_line(&mut line).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_lobby() {
+        let mut lobby = Lobby::default();
+        lobby.join("Alice").unwrap();
+        assert!(!lobby.ready());
+        lobby.join("Bob").unwrap();
+        lobby.join("Carol").unwrap();
+        assert_eq!(
+            lobby.join("Bob"),
+            Err(LobbyError::DuplicateName("Bob".to_string()))
+        );
+
+        lobby.leave("Bob").unwrap();
+        assert_eq!(
+            lobby.leave("Bob"),
+            Err(LobbyError::NotInLobby("Bob".to_string()))
+        );
+        assert!(lobby.ready());
+        assert_eq!(
+            lobby.start(),
+            Ok(vec!["Alice".to_string(), "Carol".to_string()])
+        );
+
+        // minimum players gate
+        let mut lobby = Lobby::default();
+        lobby.join("Alice").unwrap();
+        lobby.join("Bob").unwrap();
+        lobby.leave("Alice").unwrap();
+        assert!(!lobby.ready());
+        assert_eq!(lobby.start(), Err(LobbyError::NotEnoughPlayers(1)));
+    }
+
+    #[test]
+    fn test_collect_round_duplicate_name() {
+        // Bob's name is taken, so he picks another one
+        let input = format!(
+            "2\nBob\n{}\nBob\nBobby\n{}\nRock\nbob\nPaper\nbobby\n",
+            commit_faster("Rock", "bob"),
+            commit_faster("Paper", "bobby")
+        );
+        let players_details = collect_round(
+            &mut std::io::Cursor::new(input),
+            &mut RoundOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            players_details,
+            vec![
+                ("Bob".to_string(), RevealState::Revealed(Choice::Rock)),
+                ("Bobby".to_string(), RevealState::Revealed(Choice::Paper)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_choice_aliases() {
+        let path = std::env::temp_dir().join(format!("rps-aliases-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"Stone": "Rock", "Leaf": "Paper"}"#).unwrap();
+        let aliases = ChoiceAliases::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(aliases.parse("Stone"), Ok(Choice::Rock));
+        assert_eq!(aliases.parse("Scissors"), Ok(Choice::Scissors));
+        assert!(aliases.parse("Lizard").is_err());
+        assert!(ChoiceAliases::from_json(r#"{"Stone": "Boulder"}"#).is_err());
+
+        // the alias entered is what got committed
+        let input = format!(
+            "2\nAlice\n{}\nBob\n{}\nStone\nalice\n2\nbob\n",
+            commit_faster("Stone", "alice"),
+            commit_faster("Paper", "bob")
+        );
+        let mut options = RoundOptions {
+            aliases,
+            ..RoundOptions::default()
+        };
+        let players_details =
+            collect_round(&mut std::io::Cursor::new(input), &mut options).unwrap();
+        assert_eq!(
+            players_details,
+            vec![
+                ("Alice".to_string(), RevealState::Revealed(Choice::Rock)),
+                ("Bob".to_string(), RevealState::Revealed(Choice::Paper)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shuffle_players() {
+        use rand::SeedableRng;
+
+        let players_details = [
+            ("Alice", Choice::Rock),
+            ("Bob", Choice::Scissors),
+            ("Carol", Choice::Paper),
+            ("Dave", Choice::Rock),
+        ]
+        .map(|(name, choice)| (name.to_string(), RevealState::Revealed(choice)))
+        .to_vec();
+        let mut shuffled = players_details.clone();
+        shuffle_players(&mut shuffled, &mut StdRng::seed_from_u64(42));
+
+        let names = shuffled
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Dave", "Bob", "Alice", "Carol"]);
+        assert_eq!(score_round(&shuffled), score_round(&players_details));
+    }
+
+    #[test]
+    fn test_reveal_proof() {
+        let proof = RevealProof {
+            commit_hash: commit_faster("Rock", "salt:with:colons"),
+            choice: Choice::Rock,
+            salt: "salt:with:colons".to_string(),
+        };
+        let printed = proof.to_string();
+        assert_eq!(printed.parse::<RevealProof>(), Ok(proof.clone()));
+        assert!(proof.verify());
+
+        let tampered = printed
+            .replace("Rock", "Paper")
+            .parse::<RevealProof>()
+            .unwrap();
+        assert!(!tampered.verify());
+        assert!("Rock:abhi".parse::<RevealProof>().is_err());
+    }
+
+    #[test]
+    fn test_recommit_lost_salt() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut salts = SaltStore::default();
+        let mut players_details = ["Alice", "Bob"]
+            .map(|name| {
+                let commit_hash = salts.commit(name, &Choice::Rock, &mut rng);
+                (name.to_string(), RevealState::Committed(commit_hash))
+            })
+            .to_vec();
+
+        // Bob's salt got lost: he re-commits while still in the commit phase
+        salts.salts.remove("Bob");
+        assert!(salts.salt("Bob").is_none());
+        let commit_hash = recommit_lost_salt(
+            "Bob",
+            &mut players_details,
+            &mut salts,
+            &Choice::Paper,
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(players_details[1].1, RevealState::Committed(commit_hash));
+        assert!(reveal_faster(
+            commit_hash,
+            "Paper",
+            salts.salt("Bob").unwrap()
+        ));
+        assert_eq!(
+            recommit_lost_salt(
+                "Carol",
+                &mut players_details,
+                &mut salts,
+                &Choice::Rock,
+                &mut rng
+            ),
+            Err(GameError::NotInRound("Carol".to_string()))
+        );
+
+        // Alice's salt is still there, her commit can be revealed
+        assert_eq!(
+            recommit_lost_salt(
+                "Alice",
+                &mut players_details,
+                &mut salts,
+                &Choice::Paper,
+                &mut rng
+            ),
+            Err(GameError::SaltNotLost("Alice".to_string()))
+        );
+
+        // refused once Alice revealed
+        players_details[0].1 = RevealState::Revealed(Choice::Rock);
+        salts.salts.remove("Bob");
+        assert_eq!(
+            recommit_lost_salt(
+                "Bob",
+                &mut players_details,
+                &mut salts,
+                &Choice::Paper,
+                &mut rng
+            ),
+            Err(GameError::RevealsStarted)
+        );
+        assert_eq!(players_details[1].1, RevealState::Committed(commit_hash));
+    }
+
+    #[test]
+    fn test_collect_reveals_recovers_lost_salt() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut salts = SaltStore::default();
+        let mut players_details = ["Alice", "Bob"]
+            .map(|name| {
+                let commit_hash = salts.commit(name, &Choice::Rock, &mut rng);
+                (name.to_string(), RevealState::Committed(commit_hash))
+            })
+            .to_vec();
+        salts.salts.remove("Bob");
+
+        // Bob re-commits Paper, then both reveal with the tool's salts
+        let mut options = RoundOptions {
+            salts: Some(salts),
+            ..RoundOptions::default()
+        };
+        let mut out = vec![];
+        assert!(collect_reveals(
+            &mut std::io::Cursor::new("2\nRock\nPaper\n"),
+            &mut out,
+            &mut players_details,
+            &mut options,
+        )
+        .unwrap());
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Bob re-committed a new choice.\n\
+             Confirmed: you played Rock\n\
+             Confirmed: you played Paper\n"
+        );
+        assert_eq!(players_details[1].1, RevealState::Revealed(Choice::Paper));
+    }
+
+    #[test]
+    fn test_collect_round_replays_with_salts() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut salts = SaltStore::default();
+        let commits = ["Alice", "Bob"].map(|name| salts.commit(name, &Choice::Rock, &mut rng));
+
+        // both played Rock: the round is replayed, the tool committing the new choices itself
+        let mut options = RoundOptions {
+            min_distinct_choices: 2,
+            salts: Some(salts),
+            salt_rng: StdRng::seed_from_u64(8),
+            ..RoundOptions::default()
+        };
+        let input = format!(
+            "2\nAlice\n{}\nBob\n{}\nRock\nRock\nScissors\nPaper\nScissors\nPaper\n",
+            commits[0].to_hex(),
+            commits[1].to_hex()
+        );
+        let players_details =
+            collect_round(&mut std::io::Cursor::new(input), &mut options).unwrap();
+
+        assert_eq!(
+            players_details,
+            [
+                ("Alice".to_string(), RevealState::Revealed(Choice::Scissors)),
+                ("Bob".to_string(), RevealState::Revealed(Choice::Paper)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accept_reveal() {
+        let mut state = RevealState::Committed(commit_faster("Rock", "alice"));
+        accept_reveal("Alice", &mut state, Choice::Rock).unwrap();
+        assert_eq!(state, RevealState::Revealed(Choice::Rock));
+        // the same reveal again is fine
+        accept_reveal("Alice", &mut state, Choice::Rock).unwrap();
+
+        // but not a different one, even if it "verified"
+        assert_eq!(
+            accept_reveal("Alice", &mut state, Choice::Paper),
+            Err(GameError::ChangedReveal {
+                player: "Alice".to_string(),
+                revealed: Choice::Rock,
+                attempted: Choice::Paper,
+            })
+        );
+        assert_eq!(state, RevealState::Revealed(Choice::Rock));
+
+        let mut forfeit = RevealState::Forfeit { voluntary: true };
+        accept_reveal("Bob", &mut forfeit, Choice::Paper).unwrap();
+        assert_eq!(forfeit, RevealState::Forfeit { voluntary: true });
+    }
+
+    #[test]
+    fn test_reveal_confirmation() {
+        // a wrong salt first, then a valid reveal by the choice's number
+        let input = "2\nbobby\n2\nbob\n";
+        let mut players_details = vec![(
+            "Bob".to_string(),
+            RevealState::Committed(commit_faster("Paper", "bob")),
+        )];
+        let mut out = vec![];
+        assert!(collect_reveals(
+            &mut std::io::Cursor::new(input),
+            &mut out,
+            &mut players_details,
+            &mut RoundOptions::default(),
+        )
+        .unwrap());
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "that choice/salt doesn't match your committed hash\nConfirmed: you played Paper\n"
+        );
+        assert_eq!(players_details[0].1, RevealState::Revealed(Choice::Paper));
+    }
+
+    #[test]
+    fn test_reveal_lowercase_commit() {
+        // Bob committed "rock": revealing "Rock" gets the hint, then "rock" as committed verifies
+        let mut players_details = vec![(
+            "Bob".to_string(),
+            RevealState::Committed(commit_faster("rock", "bob")),
+        )];
+        let mut out = vec![];
+        assert!(collect_reveals(
+            &mut std::io::Cursor::new("Rock\nbob\nrock\nbob\n"),
+            &mut out,
+            &mut players_details,
+            &mut RoundOptions::default(),
+        )
+        .unwrap());
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "case/format mismatch: you committed a different spelling ('rock'), \
+             please reveal it as committed\n\
+             Confirmed: you played Rock\n"
+        );
+        assert_eq!(players_details[0].1, RevealState::Revealed(Choice::Rock));
+    }
+
+    #[test]
+    fn test_reveal_mismatch_feedback() {
+        let commit = || {
+            vec![(
+                "Bob".to_string(),
+                RevealState::Committed(commit_faster("Paper", "bob")),
+            )]
+        };
+
+        // an illegal choice, then a legal one that wasn't committed
+        let mut players_details = commit();
+        let mut out = vec![];
+        collect_reveals(
+            &mut std::io::Cursor::new("Lizard\nRock\nbob\nPaper\nbob\n"),
+            &mut out,
+            &mut players_details,
+            &mut RoundOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "illegal choice: Lizard\n\
+             that choice/salt doesn't match your committed hash\n\
+             Confirmed: you played Paper\n"
+        );
+
+        // only the mismatches count against the cap
+        let mut players_details = commit();
+        let mut out = vec![];
+        let mut options = RoundOptions {
+            max_reveal_attempts: NonZeroU32::new(2),
+            ..RoundOptions::default()
+        };
+        collect_reveals(
+            &mut std::io::Cursor::new("Lizard\nRock\nbob\nScissors\nbob\n"),
+            &mut out,
+            &mut players_details,
+            &mut options,
+        )
+        .unwrap();
+        assert_eq!(
+            players_details[0].1,
+            RevealState::Forfeit { voluntary: false }
+        );
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .ends_with("Too many failed reveals, Bob forfeits the round.\n"));
+    }
+
+    #[test]
+    fn test_score_round_reveal_states() {
+        let players_details = |bob| {
+            vec![
+                ("Alice".to_string(), RevealState::Revealed(Choice::Rock)),
+                ("Bob".to_string(), bob),
+                ("Carol".to_string(), RevealState::Revealed(Choice::Scissors)),
+            ]
+        };
+
+        // revealed: scored as played
+        let scores = score_round(&players_details(RevealState::Revealed(Choice::Paper))).unwrap();
+        assert_eq!([scores["Alice"], scores["Bob"], scores["Carol"]], [1, 1, 1]);
+
+        // forfeited: lost to everyone who revealed
+        let forfeited = players_details(RevealState::Forfeit { voluntary: false });
+        let scores = score_round(&forfeited).unwrap();
+        assert_eq!([scores["Alice"], scores["Bob"], scores["Carol"]], [2, 0, 1]);
+
+        // committed but not revealed yet: can't be scored
+        let committed = players_details(RevealState::Committed(commit_faster("Paper", "bob")));
+        assert_eq!(
+            score_round(&committed),
+            Err(GameError::Unrevealed("Bob".to_string()))
+        );
+        assert!(dramatic_reveal(&committed, Duration::ZERO, &mut (), &mut vec![]).is_err());
+
+        // only the moves actually played make it to the stats
+        let history = [Round {
+            id: 1,
+            winner: None,
+            players: revealed_choices(&forfeited).into_iter().collect(),
+            forfeits: HashMap::new(),
+            commit_times: HashMap::new(),
+            timestamp: 1,
+        }];
+        assert!(!history[0].players.contains_key("Bob"));
+        assert_eq!(clone_strategy(&history, "Bob").weights, [1.0; 3]);
+        assert_eq!(clone_strategy(&history, "Alice").weights, [1.0, 0.0, 0.0]);
+    }
+
+    /// Records the pauses, without waiting.
+    impl Clock for Vec<Duration> {
+        fn sleep(&mut self, duration: Duration) {
+            self.push(duration);
+        }
+    }
+
+    #[test]
+    fn test_dramatic_reveal() {
+        let players_details = [
+            ("Alice".to_string(), RevealState::Revealed(Choice::Rock)),
+            ("Bob".to_string(), RevealState::Forfeit { voluntary: true }),
+            ("Carol".to_string(), RevealState::Revealed(Choice::Scissors)),
+        ];
+        let mut delays = Vec::<Duration>::new();
+        let mut out = vec![];
+        dramatic_reveal(
+            &players_details,
+            Duration::from_millis(500),
+            &mut delays,
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Alice played Rock!\nBob forfeited!\nCarol played Scissors!\nAlice wins the round!\n"
+        );
+        // a pause before each reveal and before the winner
+        assert_eq!(delays, vec![Duration::from_millis(500); 4]);
+    }
+
+    #[test]
+    fn test_replay_game() {
+        let mut first = round(1, &[("Bob", Choice::Scissors), ("Alice", Choice::Rock)]);
+        first.winner = Some("Alice".to_string());
+        let second = round(2, &[("Alice", Choice::Paper), ("Bob", Choice::Paper)]);
+        let game = Game::from_rounds(2, vec![first, second]);
+
+        let mut delays = Vec::<Duration>::new();
+        let mut out = vec![];
+        replay_game(&game, 4.0, &mut delays, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Round 1: Alice played Rock, Bob played Scissors. Alice wins!\n\
+             Round 2: Alice played Paper, Bob played Paper. No winner.\n"
+        );
+        // a pause between the rounds, 4 times shorter
+        assert_eq!(delays, vec![Duration::from_millis(500)]);
+        assert!(replay_game(&game, 0.0, &mut (), &mut vec![]).is_err());
+        assert!(replay_game(&game, 1e-20, &mut (), &mut vec![]).is_err());
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let mut round = round(3, &[("Alice", Choice::Rock), ("Bob", Choice::Scissors)]);
+        round.winner = Some("Alice".to_string());
+        let scores = HashMap::from([("Alice".to_string(), 2), ("Bob".to_string(), 1)]);
+
+        let frame = render_frame(&round, &scores);
+        let lines = frame.lines().collect::<Vec<_>>();
+        assert!(lines[1].contains("Round 3"));
+        assert!(lines[3].contains("* Alice  Rock"));
+        assert!(lines[3].trim_end_matches(" |").ends_with('2'));
+        assert!(lines[4].contains("  Bob    Scissors"));
+        assert!(lines[4].trim_end_matches(" |").ends_with('1'));
+        // boxed
+        assert!(lines.iter().all(|line| line.len() == lines[0].len()));
+    }
+
+    #[test]
+    fn test_is_decisive() {
+        let scores = |list: &[(&str, u32)]| {
+            list.iter()
+                .map(|(name, score)| (name.to_string(), *score))
+                .collect::<HashMap<_, _>>()
+        };
+
+        let win = round_outcome(&scores(&[("Alice", 2), ("Bob", 1), ("Carol", 0)]));
+        assert_eq!(win, RoundOutcome::Win("Alice".to_string()));
+        assert!(is_decisive(&win));
+
+        let tie = round_outcome(&scores(&[("Bob", 1), ("Alice", 1), ("Carol", 0)]));
+        assert_eq!(
+            tie,
+            RoundOutcome::Tie(vec!["Alice".to_string(), "Bob".to_string()])
+        );
+        assert!(!is_decisive(&tie));
+
+        let stalemate = round_outcome(&scores(&[("Alice", 0), ("Bob", 0)]));
+        assert_eq!(stalemate, RoundOutcome::Stalemate);
+        assert!(!is_decisive(&stalemate));
+    }
+
+    #[test]
+    fn test_round_winner() {
+        let scores = |list: &[(&str, u32)]| {
+            list.iter()
+                .map(|(name, score)| (name.to_string(), *score))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert_eq!(
+            round_winner(&scores(&[("Alice", 2), ("Bob", 1)])),
+            Some("Alice".to_string())
+        );
+        assert_eq!(
+            round_winner(&scores(&[("Alice", 1), ("Bob", 1), ("Carol", 1)])),
+            None
+        );
+        assert_eq!(round_winner(&scores(&[("Alice", 0), ("Bob", 0)])), None);
+    }
+
+    #[test]
+    fn test_sub_errors_into_game_error() {
+        use std::error::Error;
+
+        fn register(count: &str, payload: &str) -> Result<String, GameError> {
+            parse_player_count(count)?;
+            let (name, _) = parse_qr_commit(payload)?;
+            Ok(name)
+        }
+
+        let payload = qr_commit_payload("Alice", commit_faster("Rock", "alice"));
+        assert_eq!(register("2", &payload), Ok("Alice".to_string()));
+
+        let err = register("1", &payload).unwrap_err();
+        assert_eq!(err, GameError::PlayerCount(PlayerCountError::TooFew(1)));
+        assert_eq!(err.to_string(), "need at least 2 players, got 1");
+        assert!(err.source().is_some());
+
+        assert_eq!(
+            register("2", "rps:commit?player=Alice"),
+            Err(GameError::QrPayload(QrPayloadError::MissingField("hash")))
+        );
+    }
+
+    #[test]
+    fn test_commit_qr() {
+        let qr = commit_qr("Alice", commit_faster("Rock", "alice")).unwrap();
+        let lines = qr.lines().collect::<Vec<_>>();
+        assert!(!lines.is_empty());
+        // square, two modules per character row
+        let width = lines[0].chars().count();
+        assert!(lines.iter().all(|line| line.chars().count() == width));
+        assert_eq!(lines.len(), width.div_ceil(2));
+
+        assert!(commit_qr(&"A".repeat(5000), commit_faster("Rock", "alice")).is_err());
+    }
+
+    #[test]
+    fn test_parse_qr_commit() {
+        let hash = commit_faster("Rock", "alice");
+        for name in ["Alice", "Tom & Jerry=100%"] {
+            let payload = qr_commit_payload(name, hash);
+            assert_eq!(parse_qr_commit(&payload), Ok((name.to_string(), hash)));
+        }
+
+        assert_eq!(
+            parse_qr_commit("https://example.com"),
+            Err(QrPayloadError::UnknownPayload)
+        );
+        assert_eq!(
+            parse_qr_commit(&format!("rps:commit?hash={hash}")),
+            Err(QrPayloadError::MissingField("player"))
+        );
+        assert_eq!(
+            parse_qr_commit("rps:commit?player=Alice&hash=4dfc"),
+            Err(QrPayloadError::InvalidHash("4dfc".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_debug_commits_sorted() {
+        let alice = (
+            "Alice".to_string(),
+            RevealState::Committed(commit_faster("Rock", "a")),
+        );
+        let bob = ("Bob".to_string(), RevealState::Forfeit { voluntary: true });
+        let carol = (
+            "Carol".to_string(),
+            RevealState::Committed(commit_faster("Paper", "c")),
+        );
+
+        let rendered = debug_commits(&[carol.clone(), alice.clone(), bob.clone()]);
+        assert_eq!(rendered, debug_commits(&[alice, bob, carol]));
+
+        let positions = ["Alice", "Bob", "Carol"].map(|name| rendered.find(name).unwrap());
+        assert!(positions[0] < positions[1] && positions[1] < positions[2]);
+    }
+
+    #[test]
+    fn test_parse_player_count() {
+        let message = |input| parse_player_count(input).unwrap_err().to_string();
+
+        assert_eq!(message("0"), "need at least 2 players, got 0");
+        assert_eq!(message("1"), "need at least 2 players, got 1");
+        assert_eq!(parse_player_count("2"), Ok(2));
+        assert_eq!(parse_player_count("64"), Ok(64));
+        assert_eq!(message("65"), "at most 64 players can play, got 65");
+        assert_eq!(
+            message("99999999999999999999"),
+            "at most 64 players can play, got 99999999999999999999"
+        );
+        assert_eq!(message("two"), "'two' isn't a number of players");
+        assert_eq!(message("-2"), "'-2' isn't a number of players");
+    }
+
+    #[test]
+    fn test_reveal_failure_policy() {
+        let commits = format!(
+            "2\nAlice\n{}\nBob\n{}\n",
+            commit_faster("Rock", "alice"),
+            commit_faster("Paper", "bob")
+        );
+        // Alice mistypes her salt
+        let bad_reveal = "Rock\nalise\n";
+
+        let mut options = RoundOptions {
+            reveal_failure: RevealFailurePolicy::ForfeitOffender,
+            ..RoundOptions::default()
+        };
+        let input = format!("{commits}{bad_reveal}Paper\nbob\n");
+        let players_details =
+            collect_round(&mut std::io::Cursor::new(input), &mut options).unwrap();
+        assert_eq!(
+            players_details[0].1,
+            RevealState::Forfeit { voluntary: false }
+        );
+        assert_eq!(players_details[1].1, RevealState::Revealed(Choice::Paper));
+
+        // the round is replayed from new commits rather than Alice forfeiting
+        let mut options = RoundOptions {
+            reveal_failure: RevealFailurePolicy::AbortRound,
+            ..RoundOptions::default()
+        };
+        let input = format!(
+            "{commits}{bad_reveal}{}\n{}\nScissors\nalice2\nRock\nbob2\n",
+            commit_faster("Scissors", "alice2"),
+            commit_faster("Rock", "bob2")
+        );
+        let players_details =
+            collect_round(&mut std::io::Cursor::new(input), &mut options).unwrap();
+        assert_eq!(
+            players_details,
+            vec![
+                ("Alice".to_string(), RevealState::Revealed(Choice::Scissors)),
+                ("Bob".to_string(), RevealState::Revealed(Choice::Rock)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_distinct_choices() {
+        let mut options = RoundOptions {
+            min_distinct_choices: 2,
+            ..RoundOptions::default()
+        };
+        // everyone plays Rock, so the round is replayed from new commits
+        let input = format!(
+            "2\nAlice\n{}\nBob\n{}\nRock\nalice\nRock\nbob\n{}\n{}\nRock\nalice2\nPaper\nbob2\n",
+            commit_faster("Rock", "alice"),
+            commit_faster("Rock", "bob"),
+            commit_faster("Rock", "alice2"),
+            commit_faster("Paper", "bob2")
+        );
+        let players_details =
+            collect_round(&mut std::io::Cursor::new(input), &mut options).unwrap();
+        assert_eq!(
+            players_details,
+            vec![
+                ("Alice".to_string(), RevealState::Revealed(Choice::Rock)),
+                ("Bob".to_string(), RevealState::Revealed(Choice::Paper)),
+            ]
+        );
+
+        // up to a cap
+        let mut input = format!(
+            "2\nAlice\n{}\nBob\n{}\n",
+            commit_faster("Rock", "alice"),
+            commit_faster("Rock", "bob"),
+        );
+        for replay in 0..=MAX_LOW_DIVERSITY_REPLAYS {
+            if replay > 0 {
+                input += &format!(
+                    "{}\n{}\n",
+                    commit_faster("Rock", "alice"),
+                    commit_faster("Rock", "bob")
+                );
+            }
+            input += "Rock\nalice\nRock\nbob\n";
+        }
+        let players_details =
+            collect_round(&mut std::io::Cursor::new(input), &mut options).unwrap();
+        assert_eq!(players_details[1].1, RevealState::Revealed(Choice::Rock));
+    }
+
+    #[test]
+    fn test_parse_round_line() {
+        assert_eq!(
+            parse_round_line("Alice=R, Bob=S,Carol=Paper"),
+            Ok(vec![
+                ("Alice".to_string(), Choice::Rock),
+                ("Bob".to_string(), Choice::Scissors),
+                ("Carol".to_string(), Choice::Paper),
+            ])
+        );
+        assert_eq!(
+            parse_round_line("Alice=R,Bob=L"),
+            Err(GameError::IllegalChoice("L".to_string()))
+        );
+        assert_eq!(
+            parse_round_line("Alice=R,Bob"),
+            Err(GameError::MalformedEntry("Bob".to_string()))
+        );
+        assert_eq!(
+            parse_round_line("=R"),
+            Err(GameError::MalformedEntry("=R".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_min_rounds_for_decisive() {
+        for scoring in [ScoringRule::EveryOpponent, ScoringRule::Knockout] {
+            assert_eq!(min_rounds_for_decisive(2, &scoring), 1);
+        }
+        assert_eq!(min_rounds_for_decisive(8, &ScoringRule::EveryOpponent), 1);
+
+        let knockout =
+            [2, 3, 4, 5, 8, 9].map(|n| min_rounds_for_decisive(n, &ScoringRule::Knockout));
+        assert_eq!(knockout, [1, 2, 2, 3, 3, 4]);
+    }
+
+    #[test]
+    fn test_rounds_to_clinch() {
+        let scores = HashMap::from([
+            ("Alice".to_string(), 7),
+            ("Bob".to_string(), 2),
+            ("Carol".to_string(), 1),
+        ]);
+        // Bob could get at most 2 * 2 more points, short of Alice's 7
+        assert_eq!(rounds_to_clinch(&scores, 2, 2), Some("Alice".to_string()));
+        // with 3 rounds left, he could catch up
+        assert_eq!(rounds_to_clinch(&scores, 3, 2), None);
+        assert_eq!(rounds_to_clinch(&HashMap::new(), 1, 1), None);
+
+        // a leader tied with someone hasn't clinched, even with no rounds left
+        let tied = HashMap::from([("Alice".to_string(), 3), ("Bob".to_string(), 3)]);
+        assert_eq!(rounds_to_clinch(&tied, 0, 1), None);
+    }
+
+    #[test]
+    fn test_match_length() {
+        assert!(MatchLength::best_of(0).is_err());
+        assert!(MatchLength::first_to(0).is_err());
+
+        let err = MatchLength::best_of(2).unwrap_err();
+        assert!(err.contains("odd"), "{err}");
+
+        assert_eq!(
+            MatchLength::best_of(3),
+            Ok(MatchLength::BestOf(NonZeroU32::new(3).unwrap()))
+        );
+        assert_eq!(
+            MatchLength::first_to(2),
+            Ok(MatchLength::FirstTo(NonZeroU32::new(2).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_longest_streak() {
+        let round = |id: u32, winner: Option<&str>| Round {
+            id,
+            winner: winner.map(String::from),
+            players: HashMap::from([
+                ("Alice".to_string(), Choice::Rock),
+                ("Bob".to_string(), Choice::Scissors),
+            ]),
+            forfeits: HashMap::new(),
+            commit_times: HashMap::new(),
+            timestamp: id,
+        };
+        let game = Game {
+            total_rounds: 4,
+            round: vec![
+                round(1, Some("Alice")),
+                round(2, Some("Alice")),
+                round(3, Some("Alice")),
+                round(4, Some("Bob")),
+            ],
+        };
+
+        assert_eq!(longest_streak(&game, "Alice"), 3);
+        assert_eq!(longest_streak(&game, "Bob"), 1);
+        assert_eq!(longest_streak(&game, "Carol"), 0);
+    }
+}
@@ -1,255 +1,753 @@
-use std::collections::HashMap;
-
-use blake3::Hash as Blake3Hash;
-use sha3::Sha3_256;
-
-// As database for maintaining scores
-struct Player {
-    name: String,
-    score: u32,
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use rust_rps_game::{
+    collect_input, collect_round, dramatic_reveal, estimate_security_bits, parse_round_line,
+    record_scores, render_frame, render_html, result_card_svg, score_of, score_round, Choice,
+    ChoiceAliases, CommitCheck, CommitConfig, Game, GameResult, HashAlgo, JsonFileStore,
+    RevealFailurePolicy, RevealProof, RevealState, RevealThrottle, Round, RoundOptions, ScoreStore,
+    SqliteStore, SystemClock, TimedLines, WeightedStrategy, DEFAULT_MAX_SALT_BYTES, SALT_BYTES,
+    SALT_EXPLANATION,
+};
+
+/// Command line options
+#[derive(Debug, PartialEq)]
+struct Args {
+    /// Read the players' answers from this named pipe (FIFO) instead of stdin,
+    /// so that another process can drive the game.
+    input_fifo: Option<PathBuf>,
+    /// Minimum time between a player's reveal attempts, no limit by default.
+    reveal_interval: Duration,
+    /// Enter the whole round on a single line, without commit-reveal.
+    practice: bool,
+    /// Largest salt accepted in a reveal.
+    max_salt_bytes: usize,
+    reveal_failure: RevealFailurePolicy,
+    /// Reveal the choices one player at a time, `dramatic_delay` apart.
+    dramatic: bool,
+    dramatic_delay: Duration,
+    /// Write an HTML summary of the game to this file.
+    report: Option<PathBuf>,
+    /// Write an SVG result card of the game to this file.
+    card: Option<PathBuf>,
+    /// Add the game's scores to the season's ones kept in this JSON file.
+    scores: Option<PathBuf>,
+    /// Or keep the season's scores & rounds in this SQLite database.
+    db: Option<PathBuf>,
+    /// Explain the commit-reveal & why salts matter before the commit phase.
+    explain: bool,
+    /// Print the round as a boxed scoreboard, optionally clearing the screen first.
+    scoreboard: bool,
+    clear: bool,
+    /// Print each commit as a QR code.
+    qr: bool,
+    max_reveal_attempts: Option<NonZeroU32>,
+    min_distinct_choices: u32,
+    /// Shuffle the reveal order each round, with this seed.
+    shuffle_players: Option<u64>,
+    /// Accept the choices' alternative names configured in this JSON file.
+    aliases: Option<PathBuf>,
+    /// Once a player revealed, the others forfeit if they take longer than this to reveal.
+    reveal_grace: Option<Duration>,
+    /// A player forfeits if they don't answer a commit or reveal prompt within this.
+    prompt_timeout: Option<Duration>,
 }
 
-/// NOTE: We can also store the game history for each player.
-/// Which option was opted by the player, who was the winner
-struct Game {
-    total_rounds: u32,
-    round: Vec<Round>,
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            input_fifo: None,
+            reveal_interval: Duration::ZERO,
+            practice: false,
+            max_salt_bytes: DEFAULT_MAX_SALT_BYTES,
+            reveal_failure: RevealFailurePolicy::Retry,
+            dramatic: false,
+            dramatic_delay: Duration::from_secs(1),
+            report: None,
+            card: None,
+            scores: None,
+            db: None,
+            explain: false,
+            scoreboard: false,
+            clear: false,
+            qr: false,
+            max_reveal_attempts: None,
+            min_distinct_choices: 0,
+            shuffle_players: None,
+            aliases: None,
+            reveal_grace: None,
+            prompt_timeout: None,
+        }
+    }
 }
 
-/// Each round of game has these fields
-struct Round {
-    id: u32,
-    // None if 'Tie'
-    winner: Option<String>,
-    players: HashMap<String, Choice>,
-    timestamp: u32,
-}
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input-fifo" => {
+                    let path = args.next().ok_or("--input-fifo expects a path")?;
+                    parsed.input_fifo = Some(PathBuf::from(path));
+                }
+                "--reveal-interval-ms" => {
+                    let millis = args
+                        .next()
+                        .and_then(|ms| ms.parse().ok())
+                        .ok_or("--reveal-interval-ms expects a number of milliseconds")?;
+                    parsed.reveal_interval = Duration::from_millis(millis);
+                }
+                "--reveal-grace-ms" => {
+                    let millis = args
+                        .next()
+                        .and_then(|ms| ms.parse().ok())
+                        .ok_or("--reveal-grace-ms expects a number of milliseconds")?;
+                    parsed.reveal_grace = Some(Duration::from_millis(millis));
+                }
+                "--prompt-timeout-ms" => {
+                    let millis = args
+                        .next()
+                        .and_then(|ms| ms.parse().ok())
+                        .ok_or("--prompt-timeout-ms expects a number of milliseconds")?;
+                    parsed.prompt_timeout = Some(Duration::from_millis(millis));
+                }
+                "--max-salt-bytes" => {
+                    parsed.max_salt_bytes = args
+                        .next()
+                        .and_then(|bytes| bytes.parse().ok())
+                        .ok_or("--max-salt-bytes expects a number of bytes")?;
+                }
+                "--max-reveal-attempts" => {
+                    parsed.max_reveal_attempts = Some(
+                        args.next()
+                            .and_then(|attempts| attempts.parse().ok())
+                            .ok_or("--max-reveal-attempts expects a positive number")?,
+                    );
+                }
+                "--min-distinct-choices" => {
+                    parsed.min_distinct_choices = args
+                        .next()
+                        .and_then(|count| count.parse().ok())
+                        .ok_or("--min-distinct-choices expects a number of choices")?;
+                }
+                "--shuffle-players" => {
+                    parsed.shuffle_players = Some(
+                        args.next()
+                            .and_then(|seed| seed.parse().ok())
+                            .ok_or("--shuffle-players expects a seed")?,
+                    );
+                }
+                "--on-reveal-failure" => {
+                    parsed.reveal_failure = match args.next().as_deref() {
+                        Some("retry") => RevealFailurePolicy::Retry,
+                        Some("forfeit") => RevealFailurePolicy::ForfeitOffender,
+                        Some("abort") => RevealFailurePolicy::AbortRound,
+                        _ => {
+                            return Err("--on-reveal-failure expects retry, forfeit or abort".into())
+                        }
+                    };
+                }
+                "--practice" => parsed.practice = true,
+                "--qr" => parsed.qr = true,
+                "--dramatic" => parsed.dramatic = true,
+                "--report" => {
+                    let path = args.next().ok_or("--report expects a path")?;
+                    parsed.report = Some(PathBuf::from(path));
+                }
+                "--card" => {
+                    let path = args.next().ok_or("--card expects a path")?;
+                    parsed.card = Some(PathBuf::from(path));
+                }
+                "--scores" => {
+                    let path = args.next().ok_or("--scores expects a path")?;
+                    parsed.scores = Some(PathBuf::from(path));
+                }
+                "--explain" => parsed.explain = true,
+                "--aliases" => {
+                    let path = args.next().ok_or("--aliases expects a path")?;
+                    parsed.aliases = Some(PathBuf::from(path));
+                }
+                "--db" => {
+                    let path = args.next().ok_or("--db expects a path")?;
+                    parsed.db = Some(PathBuf::from(path));
+                }
+                "--scoreboard" => parsed.scoreboard = true,
+                "--clear" => parsed.clear = true,
+                "--dramatic-delay-ms" => {
+                    let millis = args
+                        .next()
+                        .and_then(|ms| ms.parse().ok())
+                        .ok_or("--dramatic-delay-ms expects a number of milliseconds")?;
+                    parsed.dramatic_delay = Duration::from_millis(millis);
+                }
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
 
-/// Return hash of player's choice & salt.
-/// NOTE: Salt is added to anonymize the choice made by the user, otherwise it
-/// becomes very predictable for just 3 (or limited) choices in this case - Rock, Paper, Scissor.
-/// Suppose, for example if we salt the choice made by Alice, then the hash committed is
-/// unpredictable in terms of guessing the choice made.
-///
-/// Here, the salt is supposed to be changed on every choice made. Otherwise, the choice becomes predictable.
-///
-/// Q. Why not reveal the choice during the commit?
-/// A. This is because in the world of internet in case of online gaming, there is network latency
-/// which is inevitable as participants are most probably from different geographical locations.
-/// So, we want a system that locks the choices made and also is secret enough to not get revealed until asked for.
-///
-/// Q. Why hashing?
-/// A. This is because hashes are irreversible. And in cases of limited choices like here - Rock, Paper, Scissor.
-/// It is recommended to add 'salt' to the choice before committing the hash.
-///
-/// Q. Why is it recommended to change the salt on every move selection?
-/// A. It is done so that the choice made is unpredictable until asked to reveal.
-///
-/// Q. Why Blake3 hash function?
-/// A. It's very fast on modern computers
-///
-/// TODO: Need to check the benchmark with 2 functions
-fn commit_faster(choice: &str, salt: &str) -> Blake3Hash {
-    let mut hasher = blake3::Hasher::new();
-    hasher.update(choice.as_bytes());
-    hasher.update(salt.as_bytes());
-
-    let hash = hasher.finalize();
-
-    hash
-}
+        Ok(parsed)
+    }
 
-/// Check if the hash of inputs (choice, salt) matches with the commit_hash
-fn reveal_faster(commit_hash: Blake3Hash, choice: &str, salt: &str) -> bool {
-    let computed_hash = commit_faster(choice, salt);
+    /// Reject the contradictory options up front, rather than silently ignoring some of them
+    /// mid-game.
+    fn validate(&self) -> Result<(), String> {
+        if self.practice {
+            let commit_reveal_options = [
+                ("--explain", self.explain),
+                ("--qr", self.qr),
+                ("--reveal-interval-ms", !self.reveal_interval.is_zero()),
+                ("--max-reveal-attempts", self.max_reveal_attempts.is_some()),
+                (
+                    "--on-reveal-failure",
+                    self.reveal_failure != RevealFailurePolicy::Retry,
+                ),
+                ("--min-distinct-choices", self.min_distinct_choices > 0),
+                ("--shuffle-players", self.shuffle_players.is_some()),
+                ("--aliases", self.aliases.is_some()),
+                ("--reveal-grace-ms", self.reveal_grace.is_some()),
+                ("--prompt-timeout-ms", self.prompt_timeout.is_some()),
+            ];
+            if let Some((option, _)) = commit_reveal_options.iter().find(|(_, set)| *set) {
+                return Err(format!(
+                    "{option} can't be used with --practice, which plays without the commit-reveal"
+                ));
+            }
+        }
+        if self.clear && !self.scoreboard {
+            return Err("--clear clears the screen for --scoreboard, which isn't set".into());
+        }
+        if self.scores.is_some() && self.db.is_some() {
+            return Err("--scores & --db both keep the season's scores, pick one".into());
+        }
+        if self.min_distinct_choices > 3 {
+            return Err(format!(
+                "--min-distinct-choices {} can't be met with only 3 choices",
+                self.min_distinct_choices
+            ));
+        }
 
-    computed_hash.eq(&commit_hash)
+        Ok(())
+    }
 }
 
-// use sha3::Digest;
-
-/// Q. Why Keccak256 hash function?
-/// A. It belongs to SHA3 family which is even stronger than Blake3.
-// fn commit_stronger(choice: &str, salt: &str) -> dyn Digest {
-//     let mut hasher = Sha3_256::new();
-//     hasher.update(choice.as_bytes());
-//     hasher.update(choice.as_bytes());
-//     // read hash digest
-//     let result = hasher.finalize();
-
-//     result
-// }
-
-// fn reveal_stronger(commit_hash: String, inputs: &[String]) -> bool {}
-
-/// Define a generic function to get user input
-fn collect_input<T: std::str::FromStr>(prompt: &str) -> T {
-    loop {
-        println!("{}", prompt);
-        let mut input = String::new();
-        std::io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read input");
-        match input.trim().parse() {
-            Ok(value) => return value,
-            Err(_) => continue,
-        }
+/// The commit-reveal's explanation, if asked for with `--explain`.
+fn explain_commits(args: &Args, out: &mut impl Write) -> std::io::Result<()> {
+    if args.explain {
+        writeln!(out, "{SALT_EXPLANATION}\n")?;
     }
+
+    Ok(())
 }
 
-#[derive(PartialEq, PartialOrd, Debug)]
-enum Choice {
-    Rock,
-    Paper,
-    Scissors,
-    Empty,
+/// The commits' security level (see `estimate_security_bits`), as shown when the game starts:
+/// the players commit with Blake3, their salts as random as `generate_salt`'s.
+fn security_level(out: &mut impl Write) -> std::io::Result<()> {
+    let bits = estimate_security_bits(&CommitConfig {
+        algo: HashAlgo::Blake3,
+        salt_bits: 8 * SALT_BYTES,
+        num_choices: Choice::ALL.len(),
+    });
+    writeln!(
+        out,
+        "Security level: {bits:.0} bits, with random {SALT_BYTES}-byte salts"
+    )
 }
 
-/// Declare the winner based on the game logic.
-/// Winner may be 'None' in cases where everyone is killing everyone.
-/// Game rules:
-/// - Rock beats scissors.
-/// - Paper beats rock.
-/// - Scissors beats paper.
-///
-/// In simple line, R < P < S < R
-/// Score rules:
-/// - Tie: when players (all) select same option, score remains unchanged.
-///         Suppose 2 of 3 players select Rock, Rock, Scissor.
-///         So, 1st, 2nd player gain 1 point each & 3rd player 0
-/// - Win/Lose: when either party wins based on game rule.
-fn update_scores(
-    players_details: &Vec<(String, Blake3Hash, Choice)>,
-    players_scores: &mut HashMap<String, u32>,
-) {
-    // let choices = players_details.iter().map(|x| &x.2).collect::<Vec<_>>();
-    for i in 0..players_details.len() {
-        for j in i + 1..players_details.len() {
-            // println!("comparo b/w players: {}--{}", i, j);
-            if players_details[i].2 < players_details[j].2 {
-                if players_details[i].2 == Choice::Rock && players_details[j].2 == Choice::Scissors
-                {
-                    *players_scores
-                        .entry(players_details[i].0.clone())
-                        .or_insert(0) += 1;
-                } else {
-                    *players_scores
-                        .entry(players_details[j].0.clone())
-                        .or_insert(0) += 1;
-                }
-            } else if players_details[i].2 > players_details[j].2 {
-                *players_scores
-                    .entry(players_details[i].0.clone())
-                    .or_insert(0) += 1;
+/// `verify-proof [--algo ALGO] [PROOF]`: check a reveal proof (see `RevealProof`) of a commit
+/// made with ALGO (Blake3 by default), read from `input` if not given. Prints MATCH or NO MATCH
+/// (with a hint if the commit matches another algorithm), returning the exit code: 0 on a match,
+/// 1 if not & 2 for a malformed proof.
+fn verify_proof(
+    args: &[String],
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+) -> std::io::Result<i32> {
+    let (algo, proof) = match args {
+        [flag, algo, proof @ ..] if flag == "--algo" => match algo.parse::<HashAlgo>() {
+            Ok(algo) => (algo, proof),
+            Err(err) => {
+                eprintln!("{err}");
+                return Ok(2);
             }
+        },
+        proof => (HashAlgo::Blake3, proof),
+    };
+    let proof = match proof.first() {
+        Some(proof) => proof.clone(),
+        None => {
+            let mut line = String::new();
+            input.read_line(&mut line)?;
+            line
+        }
+    };
+
+    match proof.parse::<RevealProof>() {
+        Ok(proof) => {
+            let check = proof.check(algo);
+            writeln!(out, "{check}")?;
+            if check == CommitCheck::Match {
+                Ok(0)
+            } else {
+                Ok(1)
+            }
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            Ok(2)
         }
     }
 }
 
-fn main() {
-    // maintain a player of HashMap type as no need to sort.
-    let mut players_scores = HashMap::<String, u32>::new();
-
-    // 1. collect players' commit-hash turn-wise
-    let mut players_details = Vec::<(String, Blake3Hash, Choice)>::new();
-
-    loop {
-        // collect players count
-        // loop until player count is valid
-        let players_count = collect_input::<u32>("Enter number of players: ");
-        if players_count < 2 {
-            continue;
-        }
-
-        // collect players name & commit hashes
-        for _ in 0..players_count {
-            let player_name = collect_input::<String>("Enter your name: ");
-            let player_commit_hash = collect_input::<Blake3Hash>(
-                "Enter the commit hash of your choice (Rock, Paper, Scissors) with salt: ",
-            );
-            players_details.push((player_name.clone(), player_commit_hash, Choice::Empty));
-            players_scores.insert(player_name, 0);
+/// `choose <CHOICE> <CHOICE>`: print which of the two choices wins, or Tie. Returns the exit
+/// code, 2 for an illegal choice.
+fn choose(choices: &[String], out: &mut impl Write) -> std::io::Result<i32> {
+    let [a, b] = choices else {
+        eprintln!("usage: choose <CHOICE> <CHOICE>");
+        return Ok(2);
+    };
+    let (a, b) = match (a.parse::<Choice>(), b.parse::<Choice>()) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("{err}");
+            return Ok(2);
         }
+    };
+
+    let winner = if a.beats(&b) {
+        a.to_string()
+    } else if b.beats(&a) {
+        b.to_string()
+    } else {
+        "Tie".to_string()
+    };
+    writeln!(out, "{winner}")?;
+
+    Ok(0)
+}
 
-        break;
+/// `strategy`: print the classic ruleset's equilibrium mixed strategy (see
+/// `WeightedStrategy::equilibrium`), returning the exit code. Custom rulesets are out of scope.
+fn strategy(args: &[String], out: &mut impl Write) -> std::io::Result<i32> {
+    if !args.is_empty() {
+        eprintln!("usage: strategy");
+        return Ok(2);
     }
 
-    println!("commit hashes: {:#?}", players_details);
-
-    // 2. store to DB or the values remain on per session
+    let equilibrium = WeightedStrategy::equilibrium();
+    for (choice, weight) in Choice::ALL.iter().zip(equilibrium.weights) {
+        writeln!(out, "{choice}: {:.1}%", weight * 100.0)?;
+    }
 
-    // 3. reveal the choices & salt & verify with reveal function
-    // run in loop and ask for choice & salt. And then collect it for comparison.
-    for i in 0..players_details.len() {
-        // Keep asking (looping) the player until the choice & salt doesn't match corresponding to the committed hash.
-        loop {
-            let choice = collect_input::<String>(&format!(
-                "{}, please reveal the choice: ",
-                players_details[i].0
-            ));
+    Ok(0)
+}
 
-            let salt = collect_input::<String>("also please reveal the salt: ");
+/// Exit with a subcommand's exit code, or 1 if it failed to read its input or write its output.
+fn exit_with(code: std::io::Result<i32>) -> ! {
+    std::process::exit(code.unwrap_or_else(|err| {
+        eprintln!("{err}");
+        1
+    }))
+}
 
-            if !reveal_faster(players_details[i].1, &choice, &salt) {
-                continue;
+/// Read a practice round, given as a single line of Name=Choice pairs.
+fn practice_round(input: &mut impl BufRead) -> std::io::Result<Vec<(String, RevealState)>> {
+    loop {
+        let line = collect_input::<String>(
+            input,
+            "Enter the round as Name=Choice pairs (e.g. Alice=R,Bob=S): ",
+        )?;
+        match parse_round_line(&line) {
+            Ok(choices) => {
+                return Ok(choices
+                    .into_iter()
+                    .map(|(name, choice)| (name, RevealState::Revealed(choice)))
+                    .collect())
             }
+            Err(err) => println!("{err}"),
+        }
+    }
+}
 
-            // initialize
-            let mut choice_enum_variant = Choice::Empty;
+fn main() {
+    let mut argv = std::env::args().skip(1).peekable();
+    if argv.peek().map(String::as_str) == Some("choose") {
+        let choices = argv.skip(1).collect::<Vec<_>>();
+        exit_with(choose(&choices, &mut std::io::stdout()));
+    }
+    if argv.peek().map(String::as_str) == Some("strategy") {
+        let args = argv.skip(1).collect::<Vec<_>>();
+        exit_with(strategy(&args, &mut std::io::stdout()));
+    }
+    if argv.peek().map(String::as_str) == Some("verify-proof") {
+        let args = argv.skip(1).collect::<Vec<_>>();
+        exit_with(verify_proof(
+            &args,
+            &mut std::io::stdin().lock(),
+            &mut std::io::stdout(),
+        ));
+    }
 
-            // modify enum variant before added into players details
-            if choice == "Rock".to_string() {
-                choice_enum_variant = Choice::Rock;
-            } else if choice == "Paper".to_string() {
-                choice_enum_variant = Choice::Paper;
-            } else if choice == "Scissors".to_string() {
-                choice_enum_variant = Choice::Scissors;
+    let args = Args::parse(argv)
+        .and_then(|args| args.validate().map(|()| args))
+        .unwrap_or_else(|err| {
+            eprintln!("{err}");
+            std::process::exit(2);
+        });
+
+    let mut input: Box<dyn BufRead> = match (&args.input_fifo, args.prompt_timeout) {
+        (Some(path), timeout) => {
+            let fifo = File::open(path).unwrap_or_else(|err| {
+                eprintln!("Failed to open the input FIFO: {err}");
+                std::process::exit(1);
+            });
+            let fifo = BufReader::new(fifo);
+            match timeout {
+                Some(timeout) => Box::new(TimedLines::new(fifo, timeout)),
+                None => Box::new(fifo),
             }
+        }
+        // NOTE: the thread reading stdin lingers at exit until its line (or EOF) comes
+        (None, Some(timeout)) => {
+            Box::new(TimedLines::new(BufReader::new(std::io::stdin()), timeout))
+        }
+        (None, None) => Box::new(std::io::stdin().lock()),
+    };
+
+    let aliases = match &args.aliases {
+        Some(path) => ChoiceAliases::load(path).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }),
+        None => ChoiceAliases::default(),
+    };
+    let mut options = RoundOptions {
+        throttle: RevealThrottle::new(args.reveal_interval),
+        max_salt_bytes: args.max_salt_bytes,
+        reveal_failure: args.reveal_failure,
+        qr: args.qr,
+        max_reveal_attempts: args.max_reveal_attempts,
+        min_distinct_choices: args.min_distinct_choices,
+        shuffle_players: args.shuffle_players.map(StdRng::seed_from_u64),
+        aliases,
+        reveal_grace: args.reveal_grace,
+        // the players commit with their own salts
+        salts: None,
+        salt_rng: StdRng::from_entropy(),
+        clock: Box::new(Instant::now),
+        commit_times: Default::default(),
+    };
+    let players_details = if args.practice {
+        practice_round(&mut input)
+    } else {
+        explain_commits(&args, &mut std::io::stdout()).expect("Failed to write the explanation");
+        security_level(&mut std::io::stdout()).expect("Failed to write the security level");
+        collect_round(&mut input, &mut options)
+    };
+    // e.g. the input closed mid-round: there's no round to score
+    let players_details = players_details.unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(1);
+    });
+
+    if args.dramatic {
+        dramatic_reveal(
+            &players_details,
+            args.dramatic_delay,
+            &mut SystemClock,
+            &mut std::io::stdout(),
+        )
+        .expect("Failed to write the reveals");
+    }
+
+    let players_scores = score_round(&players_details).expect("The round is over");
 
-            // set choice variant to player
-            players_details[i].2 = choice_enum_variant;
+    let mut round = Round::played(1, &players_details, &players_scores);
+    round.commit_times = std::mem::take(&mut options.commit_times);
 
-            break;
+    if args.scoreboard {
+        if args.clear {
+            print!("\x1B[2J\x1B[H");
         }
+        println!("{}", render_frame(&round, &players_scores));
     }
 
-    // 4. update the scores
-    update_scores(&players_details, &mut players_scores);
-
     // 5. print the scores
     println!("The game score so far is:");
     for name in players_scores.keys() {
-        println!("- {name}: {}", players_scores.get(name).unwrap());
+        println!("- {name}: {}", score_of(&players_scores, name));
+    }
+
+    let store: Option<Box<dyn ScoreStore>> = if let Some(path) = &args.db {
+        let db = SqliteStore::open(path)
+            .and_then(|db| db.save_round(&round).map(|()| db))
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to save the round to the database: {err}");
+                std::process::exit(1);
+            });
+        Some(Box::new(db))
+    } else {
+        args.scores
+            .as_ref()
+            .map(|path| Box::new(JsonFileStore { path: path.clone() }) as Box<dyn ScoreStore>)
+    };
+    if let Some(store) = store {
+        let totals = record_scores(store.as_ref(), &players_scores).unwrap_or_else(|err| {
+            eprintln!("Failed to record the season score: {err}");
+            std::process::exit(1);
+        });
+        println!("The season score so far is:");
+        for name in totals.keys() {
+            println!("- {name}: {}", totals[name]);
+        }
+    }
+
+    let result = GameResult {
+        game: Game::from_rounds(1, vec![round]),
+        scores: players_scores,
+    };
+    if let Some(path) = &args.report {
+        if let Err(err) = std::fs::write(path, render_html(&result)) {
+            eprintln!("Failed to write the report: {err}");
+            std::process::exit(1);
+        }
+    }
+    if let Some(path) = &args.card {
+        if let Err(err) = std::fs::write(path, result_card_svg(&result.game, &result.scores)) {
+            eprintln!("Failed to write the result card: {err}");
+            std::process::exit(1);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use hex_literal::hex;
+    #[test]
+    fn test_args() {
+        let args = |list: &[&str]| Args::parse(list.iter().map(|a| a.to_string()));
+
+        assert_eq!(args(&[]), Ok(Args::default()));
+        assert_eq!(
+            args(&["--input-fifo", "/tmp/rps.fifo"]).unwrap().input_fifo,
+            Some(PathBuf::from("/tmp/rps.fifo"))
+        );
+        assert_eq!(
+            args(&["--reveal-interval-ms", "500"])
+                .unwrap()
+                .reveal_interval,
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            args(&["--max-salt-bytes", "64"]).unwrap().max_salt_bytes,
+            64
+        );
+        assert_eq!(
+            args(&["--report", "game.html"]).unwrap().report,
+            Some(PathBuf::from("game.html"))
+        );
+        assert_eq!(
+            args(&["--card", "card.svg"]).unwrap().card,
+            Some(PathBuf::from("card.svg"))
+        );
+        assert_eq!(
+            args(&["--scores", "scores.json"]).unwrap().scores,
+            Some(PathBuf::from("scores.json"))
+        );
+        assert_eq!(
+            args(&["--db", "season.db"]).unwrap().db,
+            Some(PathBuf::from("season.db"))
+        );
+        assert!(args(&["--explain"]).unwrap().explain);
+        assert_eq!(
+            args(&["--on-reveal-failure", "abort"])
+                .unwrap()
+                .reveal_failure,
+            RevealFailurePolicy::AbortRound
+        );
+        assert!(args(&["--on-reveal-failure", "ignore"]).is_err());
+        assert!(args(&["--practice"]).unwrap().practice);
+        assert!(args(&["--qr"]).unwrap().qr);
+        assert_eq!(
+            args(&["--max-reveal-attempts", "3"])
+                .unwrap()
+                .max_reveal_attempts,
+            NonZeroU32::new(3)
+        );
+        assert!(args(&["--max-reveal-attempts", "0"]).is_err());
+        assert_eq!(
+            args(&["--min-distinct-choices", "2"])
+                .unwrap()
+                .min_distinct_choices,
+            2
+        );
+        assert_eq!(
+            args(&["--shuffle-players", "42"]).unwrap().shuffle_players,
+            Some(42)
+        );
+        assert!(args(&["--shuffle-players", "x"]).is_err());
+        assert_eq!(
+            args(&["--reveal-grace-ms", "30000"]).unwrap().reveal_grace,
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            args(&["--prompt-timeout-ms", "60000"])
+                .unwrap()
+                .prompt_timeout,
+            Some(Duration::from_secs(60))
+        );
+        assert!(args(&["--prompt-timeout-ms"]).is_err());
+        assert_eq!(
+            args(&["--aliases", "aliases.json"]).unwrap().aliases,
+            Some(PathBuf::from("aliases.json"))
+        );
+        let scoreboard = args(&["--scoreboard", "--clear"]).unwrap();
+        assert!(scoreboard.scoreboard && scoreboard.clear);
+        let dramatic = args(&["--dramatic", "--dramatic-delay-ms", "250"]).unwrap();
+        assert!(dramatic.dramatic);
+        assert_eq!(dramatic.dramatic_delay, Duration::from_millis(250));
+        assert!(args(&["--input-fifo"]).is_err());
+        assert!(args(&["--reveal-interval-ms", "soon"]).is_err());
+        assert!(args(&["--unknown"]).is_err());
+    }
 
     #[test]
-    fn test_commit_blake3_256() {
-        let hash = commit_faster("Rock", "abhi");
-        println!("{}", hash.to_string());
-        // dbg!(hash);
+    fn test_explain_commits() {
+        let explanation = |args: &[&str]| {
+            let args = Args::parse(args.iter().map(|a| a.to_string())).unwrap();
+            let mut out = vec![];
+            explain_commits(&args, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let explained = explanation(&["--explain"]);
+        assert!(explained.contains("Change the salt on every move"));
+        assert!(explanation(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_security_level() {
+        let mut out = vec![];
+        security_level(&mut out).unwrap();
+        // capped by Blake3's 128 bits, the 16-byte salts alone giving as many
         assert_eq!(
-            hash,
-            hex!("4dfc91d264de6fa73305a7f9318cf1843575446ba36833df3177536d04d99ea7")
+            String::from_utf8(out).unwrap(),
+            "Security level: 128 bits, with random 16-byte salts\n"
         );
     }
 
     #[test]
-    fn test_reveal_blake3_256() {
-        let hash = commit_faster("rock", "abhi");
-        assert!(reveal_faster(hash, "rock", "abhi"));
+    fn test_verify_proof() {
+        let proof = |choice| {
+            format!(
+                "{}:{choice}:abhi",
+                rust_rps_game::commit_faster("Rock", "abhi")
+            )
+        };
+        let verify = |args: Vec<String>, input: &str| {
+            let mut out = vec![];
+            let code = verify_proof(
+                &args,
+                &mut std::io::Cursor::new(input.to_string()),
+                &mut out,
+            )
+            .unwrap();
+            (code, String::from_utf8(out).unwrap())
+        };
+
+        assert_eq!(verify(vec![proof("Rock")], ""), (0, "MATCH\n".to_string()));
+        // from stdin
+        assert_eq!(
+            verify(vec![], &format!("{}\n", proof("Rock"))),
+            (0, "MATCH\n".to_string())
+        );
+        assert_eq!(
+            verify(vec![proof("Paper")], ""),
+            (1, "NO MATCH\n".to_string())
+        );
+        assert_eq!(
+            verify(vec!["not a proof".to_string()], ""),
+            (2, String::new())
+        );
+        // a proof that isn't UTF-8 fails to be read: an error, rather than a panic
+        let mut unreadable = BufReader::new(std::io::Cursor::new(vec![0xff, b'\n']));
+        assert!(verify_proof(&[], &mut unreadable, &mut vec![]).is_err());
+
+        // a SHA3 commit verified as Blake3 gets a hint
+        let sha3 = format!(
+            "{}:Rock:abhi",
+            blake3::Hash::from_bytes(HashAlgo::Sha3.commit("Rock", "abhi"))
+        );
+        assert_eq!(
+            verify(vec![sha3.clone()], ""),
+            (
+                1,
+                "NO MATCH: This commit doesn't match the selected algorithm; try --algo sha3\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            verify(vec!["--algo".to_string(), "sha3".to_string(), sha3], ""),
+            (0, "MATCH\n".to_string())
+        );
+        assert_eq!(
+            verify(vec!["--algo".to_string(), "md5".to_string()], ""),
+            (2, String::new())
+        );
+    }
+
+    #[test]
+    fn test_validate_args() {
+        let validate = |args: &[&str]| {
+            Args::parse(args.iter().map(|arg| arg.to_string()))
+                .unwrap()
+                .validate()
+        };
+
+        assert_eq!(validate(&[]), Ok(()));
+        assert_eq!(
+            validate(&["--practice", "--scoreboard", "--clear", "--db", "season.db"]),
+            Ok(())
+        );
+        assert_eq!(
+            validate(&["--practice", "--qr"]),
+            Err("--qr can't be used with --practice, which plays without the commit-reveal".into())
+        );
+        assert!(validate(&["--practice", "--prompt-timeout-ms", "60000"]).is_err());
+        assert!(validate(&["--practice", "--on-reveal-failure", "abort"]).is_err());
+        assert!(validate(&["--clear"]).is_err());
+        assert!(validate(&["--scores", "season.json", "--db", "season.db"]).is_err());
+        assert!(validate(&["--min-distinct-choices", "4"]).is_err());
     }
 
     #[test]
-    fn test_update_scores() {
-        todo!()
-        // define a players details (fetch from `sample.json` file)
+    fn test_choose() {
+        let choose = |choices: [&str; 2]| {
+            let mut out = vec![];
+            let code = choose(&choices.map(String::from), &mut out).unwrap();
+            (code, String::from_utf8(out).unwrap())
+        };
+
+        assert_eq!(choose(["Rock", "Scissors"]), (0, "Rock\n".to_string()));
+        assert_eq!(choose(["3", "1"]), (0, "Rock\n".to_string()));
+        assert_eq!(choose(["Rock", "Rock"]), (0, "Tie\n".to_string()));
+        assert_eq!(choose(["Rock", "Lizard"]), (2, String::new()));
+    }
 
-        // define a players scores list
+    #[test]
+    fn test_strategy() {
+        let mut out = vec![];
+        assert_eq!(strategy(&[], &mut out).unwrap(), 0);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Rock: 33.3%\nPaper: 33.3%\nScissors: 33.3%\n"
+        );
+        assert_eq!(
+            strategy(&["lizardspock".to_string()], &mut vec![]).unwrap(),
+            2
+        );
     }
 }
@@ -0,0 +1,117 @@
+//! Allocation checks, in their own test binary: counting the allocations means swapping the
+//! global allocator, which the library's own tests shouldn't run under.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use std::io::{BufReader, Read};
+
+use rust_rps_game::{collect_salt, update_scores, Answer, Choice, GameError};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static ALLOCATED_BYTES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts the allocations (& their bytes) made by each (test) thread, so that a test can check
+/// how many a call makes without being disturbed by the tests running in parallel.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        let _ = ALLOCATED_BYTES.try_with(|bytes| bytes.set(bytes.get() + layout.size()));
+        System.alloc(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATED_BYTES
+            .try_with(|bytes| bytes.set(bytes.get() + new_size.saturating_sub(layout.size())));
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn allocated_bytes_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATED_BYTES.with(Cell::get);
+    f();
+    ALLOCATED_BYTES.with(Cell::get) - before
+}
+
+#[test]
+fn test_update_scores_allocations() {
+    // half Rock players against half Scissors players, all already on the scoreboard
+    let scored = |count: usize| {
+        let players_details = (0..count)
+            .map(|i| {
+                let choice = if i % 2 == 0 {
+                    Choice::Rock
+                } else {
+                    Choice::Scissors
+                };
+                (format!("player{i}"), choice)
+            })
+            .collect::<Vec<_>>();
+        let mut players_scores = players_details
+            .iter()
+            .map(|(name, _)| (name.clone(), 0))
+            .collect::<HashMap<_, _>>();
+        let allocations =
+            allocations_during(|| update_scores(&players_details, &mut players_scores));
+        (allocations, players_scores)
+    };
+
+    // 10x the players, 100x the pairs & points: no allocation per pair nor per point
+    let (few, _) = scored(4);
+    let (many, players_scores) = scored(40);
+    assert!(
+        many <= few,
+        "{many} allocations for 40 players, {few} for 4"
+    );
+    // nor a name cloned for the players already on the scoreboard
+    assert!(many < 40, "{many} allocations for 40 players");
+    assert_eq!(players_scores["player0"], 20);
+    assert_eq!(players_scores["player1"], 0);
+}
+
+#[test]
+fn test_collect_salt_doesnt_buffer_huge_salt() {
+    // a 64 MiB salt line, generated as it's read
+    const LEN: usize = 64 << 20;
+    let mut input = BufReader::new(
+        std::io::repeat(b's')
+            .take(LEN as u64)
+            .chain(&b"\nabhi\n"[..]),
+    );
+
+    let mut salt = None;
+    let bytes = allocated_bytes_during(|| {
+        salt = Some(collect_salt(&mut input, "salt:", 64).unwrap());
+    });
+    assert_eq!(
+        salt,
+        Some(Err(GameError::SaltTooLarge { len: LEN, max: 64 }))
+    );
+    assert!(
+        bytes < 64 * 1024,
+        "{bytes} bytes allocated for a {LEN} bytes salt"
+    );
+
+    assert_eq!(
+        collect_salt(&mut input, "salt:", 64).unwrap(),
+        Ok(Answer::Value("abhi".to_string()))
+    );
+}
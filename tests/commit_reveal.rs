@@ -0,0 +1,42 @@
+//! The full commit -> reveal -> score pipeline of a round, through the library's API.
+
+use rand::{rngs::StdRng, SeedableRng};
+use rust_rps_game::{
+    commit_faster, designated_reveal, generate_salt, round_winner, score_round, Choice, RevealState,
+};
+
+#[test]
+fn test_two_players_commit_reveal_score() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let players = [("Alice", Choice::Paper), ("Bob", Choice::Rock)];
+
+    // 1. each player commits their choice with a fresh salt
+    let commits = players
+        .iter()
+        .map(|(name, choice)| {
+            let salt = generate_salt(&mut rng);
+            let commit_hash = commit_faster(choice.as_str(), &salt);
+            (name.to_string(), choice.clone(), salt, commit_hash)
+        })
+        .collect::<Vec<_>>();
+
+    // 2. once everyone committed, they reveal: the choice must match the commit
+    let players_details = commits
+        .iter()
+        .map(|(name, choice, salt, commit_hash)| {
+            let (choice, verified) = designated_reveal(*commit_hash, choice.clone(), salt);
+            assert!(verified, "{name}'s reveal didn't verify");
+            (name.clone(), RevealState::Revealed(choice))
+        })
+        .collect::<Vec<_>>();
+
+    // a different choice doesn't verify against the same commit
+    let (_, _, alice_salt, alice_commit) = &commits[0];
+    assert!(!designated_reveal(*alice_commit, Choice::Scissors, alice_salt).1);
+
+    // 3. score the round: Paper beats Rock
+    let players_scores = score_round(&players_details).unwrap();
+    assert_eq!(players_scores["Alice"], 1);
+    assert_eq!(players_scores["Bob"], 0);
+    assert_eq!(round_winner(&players_scores).as_deref(), Some("Alice"));
+}